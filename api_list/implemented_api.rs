@@ -432,27 +432,27 @@ pub type Dart_WeakPersistentHandleFinalizer = ::std::option::Option<
 
 // pub fn Dart_IdentityEquals(obj1: Dart_Handle, obj2: Dart_Handle) -> bool;
 
-pub fn Dart_HandleFromPersistent(object: Dart_PersistentHandle) -> Dart_Handle;
+// pub fn Dart_HandleFromPersistent(object: Dart_PersistentHandle) -> Dart_Handle;
 
-pub fn Dart_HandleFromWeakPersistent(object: Dart_WeakPersistentHandle) -> Dart_Handle;
+// pub fn Dart_HandleFromWeakPersistent(object: Dart_WeakPersistentHandle) -> Dart_Handle;
 
-pub fn Dart_NewPersistentHandle(object: Dart_Handle) -> Dart_PersistentHandle;
+// pub fn Dart_NewPersistentHandle(object: Dart_Handle) -> Dart_PersistentHandle;
 
 pub fn Dart_SetPersistentHandle(obj1: Dart_PersistentHandle, obj2: Dart_Handle);
 
 pub fn Dart_DeletePersistentHandle(object: Dart_PersistentHandle);
 
-pub fn Dart_NewWeakPersistentHandle(
-    object: Dart_Handle,
-    peer: *mut ::std::os::raw::c_void,
-    external_allocation_size: isize,
-    callback: Dart_WeakPersistentHandleFinalizer,
-) -> Dart_WeakPersistentHandle;
+// pub fn Dart_NewWeakPersistentHandle(
+//     object: Dart_Handle,
+//     peer: *mut ::std::os::raw::c_void,
+//     external_allocation_size: isize,
+//     callback: Dart_WeakPersistentHandleFinalizer,
+// ) -> Dart_WeakPersistentHandle;
 
-pub fn Dart_DeleteWeakPersistentHandle(
-    isolate: Dart_Isolate,
-    object: Dart_WeakPersistentHandle,
-);
+// pub fn Dart_DeleteWeakPersistentHandle(
+//     isolate: Dart_Isolate,
+//     object: Dart_WeakPersistentHandle,
+// );
 
 // pub fn Dart_VersionString() -> *const ::std::os::raw::c_char;
 pub struct Dart_QualifiedFunctionName {
@@ -618,7 +618,7 @@ pub fn Dart_DebugName() -> Dart_Handle;
 
 pub fn Dart_IsolateServiceId(isolate: Dart_Isolate) -> *const ::std::os::raw::c_char;
 
-pub fn Dart_EnterIsolate(isolate: Dart_Isolate);
+// pub fn Dart_EnterIsolate(isolate: Dart_Isolate);
 
 pub fn Dart_KillIsolate(isolate: Dart_Isolate);
 
@@ -640,7 +640,7 @@ pub fn Dart_AddSymbols(
     buffer_size: isize,
 );
 
-pub fn Dart_ExitIsolate();
+// pub fn Dart_ExitIsolate();
 
 pub fn Dart_CreateSnapshot(
     vm_snapshot_data_buffer: *mut *mut u8,
@@ -661,21 +661,21 @@ pub fn Dart_SetMessageNotifyCallback(message_notify_callback: Dart_MessageNotify
 
 pub fn Dart_GetMessageNotifyCallback() -> Dart_MessageNotifyCallback;
 
-pub fn Dart_ShouldPauseOnStart() -> bool;
+// pub fn Dart_ShouldPauseOnStart() -> bool;
 
-pub fn Dart_SetShouldPauseOnStart(should_pause: bool);
+// pub fn Dart_SetShouldPauseOnStart(should_pause: bool);
 
-pub fn Dart_IsPausedOnStart() -> bool;
+// pub fn Dart_IsPausedOnStart() -> bool;
 
-pub fn Dart_SetPausedOnStart(paused: bool);
+// pub fn Dart_SetPausedOnStart(paused: bool);
 
-pub fn Dart_ShouldPauseOnExit() -> bool;
+// pub fn Dart_ShouldPauseOnExit() -> bool;
 
-pub fn Dart_SetShouldPauseOnExit(should_pause: bool);
+// pub fn Dart_SetShouldPauseOnExit(should_pause: bool);
 
-pub fn Dart_IsPausedOnExit() -> bool;
+// pub fn Dart_IsPausedOnExit() -> bool;
 
-pub fn Dart_SetPausedOnExit(paused: bool);
+// pub fn Dart_SetPausedOnExit(paused: bool);
 
 pub fn Dart_SetStickyError(error: Dart_Handle);
 
@@ -689,10 +689,10 @@ pub fn Dart_HandleServiceMessages() -> bool;
 
 pub fn Dart_HasServiceMessages() -> bool;
 
-pub fn Dart_RunLoop() -> Dart_Handle;
+// pub fn Dart_RunLoop() -> Dart_Handle;
 
 // pub fn Dart_GetMainPortId() -> Dart_Port;
-pub fn Dart_HasLivePorts() -> bool;
+// pub fn Dart_HasLivePorts() -> bool;
 
 // pub fn Dart_Post(port_id: Dart_Port, object: Dart_Handle) -> bool;
 // pub fn Dart_NewSendPort(port_id: Dart_Port) -> Dart_Handle;
@@ -846,11 +846,11 @@ pub fn Dart_StringToLatin1(
     length: *mut isize,
 ) -> Dart_Handle;
 
-pub fn Dart_StringToUTF16(
-    str: Dart_Handle,
-    utf16_array: *mut u16,
-    length: *mut isize,
-) -> Dart_Handle;
+// pub fn Dart_StringToUTF16(
+//     str: Dart_Handle,
+//     utf16_array: *mut u16,
+//     length: *mut isize,
+// ) -> Dart_Handle;
 
 // pub fn Dart_StringStorageSize(str: Dart_Handle, size: *mut isize) -> Dart_Handle;
 
@@ -893,12 +893,12 @@ pub fn Dart_ListGetAsBytes(
     length: isize,
 ) -> Dart_Handle;
 
-pub fn Dart_ListSetAsBytes(
-    list: Dart_Handle,
-    offset: isize,
-    native_array: *const u8,
-    length: isize,
-) -> Dart_Handle;
+// pub fn Dart_ListSetAsBytes(
+//     list: Dart_Handle,
+//     offset: isize,
+//     native_array: *const u8,
+//     length: isize,
+// ) -> Dart_Handle;
 
 // pub fn Dart_MapGetAt(map: Dart_Handle, key: Dart_Handle) -> Dart_Handle;
 
@@ -946,14 +946,14 @@ pub enum Dart_TypedData_Type {
 
 pub fn Dart_NewByteBuffer(typed_data: Dart_Handle) -> Dart_Handle;
 
-pub fn Dart_TypedDataAcquireData(
-    object: Dart_Handle,
-    type_: *mut Dart_TypedData_Type,
-    data: *mut *mut ::std::os::raw::c_void,
-    len: *mut isize,
-) -> Dart_Handle;
+// pub fn Dart_TypedDataAcquireData(
+//     object: Dart_Handle,
+//     type_: *mut Dart_TypedData_Type,
+//     data: *mut *mut ::std::os::raw::c_void,
+//     len: *mut isize,
+// ) -> Dart_Handle;
 
-pub fn Dart_TypedDataReleaseData(object: Dart_Handle) -> Dart_Handle;
+// pub fn Dart_TypedDataReleaseData(object: Dart_Handle) -> Dart_Handle;
 
 pub fn Dart_GetDataFromByteBuffer(byte_buffer: Dart_Handle) -> Dart_Handle;
 
@@ -1210,7 +1210,7 @@ pub fn Dart_SetRootLibrary(library: Dart_Handle) -> Dart_Handle;
 
 pub fn Dart_GetLoadedLibraries() -> Dart_Handle;
 
-pub fn Dart_LookupLibrary(url: Dart_Handle) -> Dart_Handle;
+// pub fn Dart_LookupLibrary(url: Dart_Handle) -> Dart_Handle;
 
 pub fn Dart_LibraryHandleError(library: Dart_Handle, error: Dart_Handle) -> Dart_Handle;
 
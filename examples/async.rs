@@ -3,7 +3,7 @@
 use dart::{create_init_function, export_dart_functions};
 
 use dart::dart_cobject::{CObject, TypedDataArray};
-use dart::dart_handle::Port;
+use dart::dart_handle::{Port, SendPort};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
@@ -15,7 +15,7 @@ fn random_array(message: CObject, _port: Port) {
                 .map(|_| rng.gen::<u8>())
                 .collect::<Vec<u8>>();
             unsafe {
-                let port = Port::from_port(port.0.id).unwrap();
+                let port = SendPort::from_port(port.0.id).unwrap();
                 if port.post_cobject(
                     CObject::TypedData(TypedDataArray::create(v).recast()),
                 ) {
@@ -0,0 +1,102 @@
+#![crate_type = "cdylib"]
+//!
+//! Drives the message loop of a helper isolate from a Rust thread.
+//!
+//! The dart side passes in a kernel file for the helper, compiled with
+//! `dart compile kernel helper.dart`, whose `main` sets up its ports:
+//!
+//! ```dart
+//! import 'dart:io';
+//! import 'dart-ext:helper_isolate';
+//!
+//! void startHelper(Uint8List kernel) native "startHelper";
+//! void stopHelper() native "stopHelper";
+//!
+//! void main() {
+//!   startHelper(File('helper.dill').readAsBytesSync());
+//!   // ...
+//!   stopHelper();
+//! }
+//! ```
+//!
+
+use dart::{create_init_function, dart_unwrap, export_dart_functions};
+
+use dart::dart_handle::{pump_events, DartScope, IsolateScope, UnverifiedDartHandle};
+use dart::prelude::*;
+use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn run_helper(kernel: Vec<u8>) {
+    let script_uri = CString::new("helper.dart").unwrap();
+    let name = CString::new("helper").unwrap();
+    let mut error = std::ptr::null_mut();
+    // Creating the isolate makes it current on this thread.
+    let isolate = unsafe {
+        dart_sys::Dart_CreateIsolateGroupFromKernel(
+            script_uri.as_ptr(),
+            name.as_ptr(),
+            kernel.as_ptr(),
+            kernel.len() as _,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut error,
+        )
+    };
+    if isolate.is_null() {
+        let message = unsafe { CStr::from_ptr(error) };
+        eprintln!("Could not create helper isolate: {:?}", message);
+        return;
+    }
+    unsafe {
+        dart_sys::Dart_ExitIsolate();
+        let error = dart_sys::Dart_IsolateMakeRunnable(isolate);
+        if !error.is_null() {
+            eprintln!("Could not start helper isolate: {:?}", CStr::from_ptr(error));
+            return;
+        }
+    }
+
+    unsafe {
+        let _isolate = IsolateScope::enter(isolate);
+        let _scope = DartScope::enter();
+        let library = UnverifiedDartHandle::new(dart_sys::Dart_RootLibrary());
+        if let Err(e) = library.invoke(*DString::new("main"), &mut []) {
+            eprintln!("Helper isolate's main failed: {:?}", e);
+        }
+    }
+
+    while RUNNING.load(Ordering::SeqCst) {
+        // Enter the isolate only for a short while at a time.
+        let _isolate = unsafe { IsolateScope::enter(isolate) };
+        let _scope = unsafe { DartScope::enter() };
+        if let Err(e) = pump_events(Duration::from_millis(100)) {
+            eprintln!("Helper isolate failed to handle a message: {:?}", e);
+            break;
+        }
+    }
+
+    unsafe {
+        dart_sys::Dart_EnterIsolate(isolate);
+        dart_sys::Dart_ShutdownIsolate();
+    }
+}
+
+fn start_helper(arguments: NativeArguments) {
+    let kernel = dart_unwrap!(arguments.get_typed_list_arg::<u8>(0));
+    let kernel = kernel.as_typed_data().unwrap().to_vec();
+    if !RUNNING.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(move || run_helper(kernel));
+    }
+}
+
+fn stop_helper(_arguments: NativeArguments) {
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+export_dart_functions!(helper_exports: ["startHelper" -> start_helper], ["stopHelper" -> stop_helper]);
+create_init_function!(helper_isolate, [helper_exports]);
@@ -0,0 +1,47 @@
+#![crate_type = "cdylib"]
+
+use dart::{create_init_function, dart_type, dart_unwrap, export_dart_functions};
+
+use dart::dart_handle::Error;
+use dart::prelude::*;
+
+dart_type! {
+    ///
+    /// The `Point` class from the dart side of this example:
+    ///
+    /// ```dart
+    /// class Point {
+    ///   final double x;
+    ///   final double y;
+    ///   Point(this.x, this.y);
+    /// }
+    /// ```
+    ///
+    pub struct Point("package:point/point.dart", "Point");
+}
+
+impl Point {
+    fn coordinate(&self, name: &str) -> f64 {
+        let value = dart_unwrap!(Dynamic::from(*self).get_field(DString::new(name)));
+        Double::from_handle(*value).ok().unwrap().value()
+    }
+
+    pub fn x(&self) -> f64 {
+        self.coordinate("x")
+    }
+
+    pub fn y(&self) -> f64 {
+        self.coordinate("y")
+    }
+}
+
+fn point_length(arguments: NativeArguments) {
+    let point = Point::from_handle(arguments.get_native_argument(0))
+        .map_err(|_| Error::new_api("Expected a Point.").unwrap());
+    let point = dart_unwrap!(point);
+    let length = point.x().hypot(point.y());
+    arguments.set_return(*Double::new(length));
+}
+
+export_dart_functions!(point_exports: ["pointLength" -> point_length]);
+create_init_function!(point, [point_exports]);
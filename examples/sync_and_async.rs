@@ -34,7 +34,7 @@ fn random_array(message: CObject, _port: Port) {
                 .map(|_| rng.gen::<u8>())
                 .collect::<Vec<u8>>();
             unsafe {
-                let port = Port::from_port(port.0.id).unwrap();
+                let port = SendPort::from_port(port.0.id).unwrap();
                 if port.post_cobject(
                     CObject::TypedData(TypedDataArray::create(v).recast()),
                 ) {
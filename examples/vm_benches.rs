@@ -0,0 +1,98 @@
+#![crate_type = "cdylib"]
+//!
+//! Benchmarks which need a running Dart VM. Build them in release
+//! mode with `cargo build --release --example vm_benches` and run
+//! them from Dart:
+//!
+//! ```dart
+//! import 'dart-ext:vm_benches';
+//!
+//! List runBenches() native "runBenches";
+//!
+//! void main() => runBenches().forEach(print);
+//! ```
+//!
+
+use dart::{create_init_function, export_dart_functions};
+
+use dart::dart_handle::DartScope;
+use dart::prelude::*;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 100_000;
+const ITERATIONS: u32 = 10;
+
+const BENCHES: &[(&str, fn())] = &[
+    ("write_back_bulk", write_back_bulk),
+    ("write_back_per_element", write_back_per_element),
+    ("read_to_boxed_slice", read_to_boxed_slice),
+    ("read_to_vec_per_element", read_to_vec_per_element),
+];
+
+///
+/// Writes a `List<i32>` through a `ListViewMut`, which writes the
+/// modified elements back to the typed data in bulk once dropped.
+///
+fn write_back_bulk() {
+    let mut list = List::<i32>::new_data(vec![0; LEN]);
+    let mut view = list.slice_mut(..);
+    for idx in 0..LEN {
+        view[idx] = Integer::new(idx as i64);
+    }
+}
+
+///
+/// Writes a `List<i32>` one element at a time, making a call into
+/// the VM for each of them.
+///
+fn write_back_per_element() {
+    let mut list = List::<i32>::new_data(vec![0; LEN]);
+    for idx in 0..LEN {
+        list.set_at(idx, Integer::new(idx as i64)).unwrap();
+    }
+}
+
+///
+/// Copies a `List<i32>` into Rust through
+/// [`to_boxed_slice`](List::to_boxed_slice), which copies the typed
+/// data in one go.
+///
+fn read_to_boxed_slice() {
+    let list = List::<i32>::new_data(vec![1; LEN]);
+    let data = list.to_boxed_slice();
+    assert_eq!(data.len(), LEN);
+}
+
+///
+/// Copies a `List<i32>` into a `Vec` one element at a time, making
+/// a call into the VM for each of them.
+///
+fn read_to_vec_per_element() {
+    let list = List::<i32>::new_data(vec![1; LEN]);
+    let data = (0..list.length())
+        .map(|idx| list.get_at(idx).unwrap().value() as i32)
+        .collect::<Vec<_>>();
+    assert_eq!(data.len(), LEN);
+}
+
+fn run_benches(arguments: NativeArguments) {
+    let mut results = Vec::new();
+    for (name, bench) in BENCHES {
+        let mut total = Duration::default();
+        for _ in 0..ITERATIONS {
+            let _scope = unsafe { DartScope::enter() };
+            let start = Instant::now();
+            bench();
+            total += start.elapsed();
+        }
+        results.push(format!("{}: {:?} per iteration", name, total / ITERATIONS));
+    }
+    let mut list = List::new_dynamic(results.len());
+    for (idx, result) in results.iter().enumerate() {
+        list.set_at(idx, *DString::new(result)).unwrap();
+    }
+    arguments.set_return(*list);
+}
+
+export_dart_functions!(bench_exports: ["runBenches" -> run_benches]);
+create_init_function!(vm_benches, [bench_exports]);
@@ -0,0 +1,578 @@
+#![crate_type = "cdylib"]
+//!
+//! Tests which need a running Dart VM, and as such can't be run by
+//! `cargo test`. Build them with `cargo build --example vm_tests`
+//! and run them from Dart:
+//!
+//! ```dart
+//! import 'dart:io';
+//! import 'dart-ext:vm_tests';
+//!
+//! List runTests() native "runTests";
+//! List checkAsyncTests() native "checkAsyncTests";
+//!
+//! Future<void> main() async {
+//!   final failures = runTests().toList();
+//!   // Lets the futures started by `runTests` complete.
+//!   await Future.delayed(const Duration(milliseconds: 100));
+//!   failures.addAll(checkAsyncTests());
+//!   failures.forEach(print);
+//!   exit(failures.isEmpty ? 0 : 1);
+//! }
+//! ```
+//!
+//! Tests of optional features only run when the example is built
+//! with them enabled, such as with `--features debug-hooks`.
+//!
+//! Tests in `ASYNC_TESTS` wait on the event loop, so `runTests` only
+//! starts them and `checkAsyncTests` checks their results.
+//!
+//! Each test runs in its own scope. Tests must report failures by
+//! panicking (`assert!`, `unwrap`, etc.) rather than with
+//! `dart_unwrap!`, since propagating an error would abort the run.
+//!
+
+use dart::{create_init_function, export_dart_functions};
+
+use dart::dart_handle::{
+    drive_events, DartScope, Error, ErrorKind, UnverifiedDartHandle, WeakPersistentHandle,
+};
+use dart::prelude::*;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::panic::catch_unwind;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
+
+const TESTS: &[(&str, fn())] = &[
+    ("error_source_is_exception", error_source_is_exception),
+    (
+        "equal_api_errors_compare_equal",
+        equal_api_errors_compare_equal,
+    ),
+    ("nul_errors_report_position", nul_errors_report_position),
+    ("to_json_string_encodes_list", to_json_string_encodes_list),
+    ("drive_events_stops_when_done", drive_events_stops_when_done),
+    (
+        "interned_strings_are_identical",
+        interned_strings_are_identical,
+    ),
+    ("type_names_of_double", type_names_of_double),
+    (
+        "integer_aggregates_with_negatives",
+        integer_aggregates_with_negatives,
+    ),
+    (
+        "double_aggregates_with_negatives",
+        double_aggregates_with_negatives,
+    ),
+    ("deep_clone_does_not_alias", deep_clone_does_not_alias),
+    ("ord_doubles_sort_nans_last", ord_doubles_sort_nans_last),
+    ("set_new_uses_element_type", set_new_uses_element_type),
+    (
+        "map_type_is_the_map_interface",
+        map_type_is_the_map_interface,
+    ),
+    ("integer_bit_ops_match_dart", integer_bit_ops_match_dart),
+    ("integer_mod_pow_matches_dart", integer_mod_pow_matches_dart),
+    (
+        "from_handle_checked_rejects_mistyped_lists",
+        from_handle_checked_rejects_mistyped_lists,
+    ),
+    (
+        "from_chars_encodes_surrogate_pairs",
+        from_chars_encodes_surrogate_pairs,
+    ),
+    (
+        "set_at_checked_rejects_mistyped_items",
+        set_at_checked_rejects_mistyped_items,
+    ),
+    (
+        "weak_handles_are_cleared_by_gc",
+        weak_handles_are_cleared_by_gc,
+    ),
+    #[cfg(feature = "debug-hooks")]
+    ("posting_arrays_frees_them", posting_arrays_frees_them),
+    #[cfg(feature = "debug-hooks")]
+    ("scoped_hook_balances_scopes", scoped_hook_balances_scopes),
+    #[cfg(feature = "metrics")]
+    ("call_hook_reports_duration", call_hook_reports_duration),
+];
+
+const ASYNC_TESTS: &[(&str, fn(), fn())] = &[
+    (
+        "future_completes_through_port",
+        start_future_completes_through_port,
+        check_future_completes_through_port,
+    ),
+    (
+        "failed_future_is_caught",
+        start_failed_future_is_caught,
+        check_failed_future_is_caught,
+    ),
+];
+
+fn error_source_is_exception() {
+    let exception = *DString::new("boom");
+    let error = Error::new_unhandled_exception(exception);
+    let source = std::error::Error::source(&error).expect("Exceptions have a source");
+    let source = source.downcast_ref::<Error>().unwrap();
+    let contained = source.get_exception().unwrap();
+    assert!(UnverifiedDartHandle::identity_eq(contained, exception));
+    assert!(std::error::Error::source(source).is_none());
+}
+
+fn equal_api_errors_compare_equal() {
+    let a = Error::new_api("mismatch").unwrap();
+    let b = Error::new_api_fmt(format_args!("mis{}", "match"));
+    assert!(a == b);
+    assert!(a != Error::new_api("other").unwrap());
+    let exception = Error::new_unhandled_exception(*DString::new("mismatch"));
+    assert!(a != exception);
+}
+
+fn nul_errors_report_position() {
+    let nul = std::ffi::CString::new("ab\0c").unwrap_err();
+    let error = Error::from(nul);
+    assert_eq!(error.kind(), ErrorKind::Api);
+    let message = error.get_msg();
+    assert!(message.to_string_lossy().contains("position 2"));
+}
+
+fn to_json_string_encodes_list() {
+    let mut list = List::<Integer>::new_integer(3);
+    for (idx, x) in [1, -2, 3].iter().enumerate() {
+        list.set_at(idx, Integer::new(*x)).unwrap();
+    }
+    // The cached `jsonEncode` must outlive the scope it was found in.
+    {
+        let _scope = unsafe { DartScope::enter() };
+        assert_eq!(list.to_json_string().unwrap(), "[1,-2,3]");
+    }
+    assert_eq!(list.to_json_string().unwrap(), "[1,-2,3]");
+}
+
+fn drive_events_stops_when_done() {
+    // Messages can't be handled while this native function is running,
+    // so this only checks that `done` is asked before waiting.
+    let mut calls = 0;
+    drive_events(Duration::from_millis(1), || {
+        calls += 1;
+        true
+    })
+    .unwrap();
+    assert_eq!(calls, 1);
+}
+
+fn interned_strings_are_identical() {
+    let first = {
+        let _scope = unsafe { DartScope::enter() };
+        DString::interned("ok").as_string()
+    };
+    assert_eq!(first, "ok");
+    let a = DString::interned("ok");
+    let b = DString::interned("ok");
+    assert!(UnverifiedDartHandle::identity_eq(*a, *b));
+    assert!(!UnverifiedDartHandle::identity_eq(
+        *a,
+        *DString::interned("no")
+    ));
+}
+
+fn type_names_of_double() {
+    let double = Double::new(1.5).to_dynamic();
+    assert_eq!(double.type_name(), "double");
+    assert_eq!(double.runtime_type_name(), "_Double");
+}
+
+fn integer_aggregates_with_negatives() {
+    let values = [-5, 3, -1, 7];
+    let mut objects = List::<Integer>::new_integer(values.len());
+    for (idx, x) in values.iter().enumerate() {
+        objects.set_at(idx, Integer::new(*x as i64)).unwrap();
+    }
+    let typed = List::<Integer>::from_handle(*List::<i8>::new_data(values.to_vec()))
+        .ok()
+        .unwrap();
+    for list in &[objects, typed] {
+        assert_eq!(list.sum(), 4);
+        assert_eq!(list.min(), Some(-5));
+        assert_eq!(list.max(), Some(7));
+    }
+    let empty = List::<Integer>::new_integer(0);
+    assert_eq!(empty.sum(), 0);
+    assert_eq!(empty.min(), None);
+}
+
+fn double_aggregates_with_negatives() {
+    let values = [-2.5, 1.0, -0.5, 4.0];
+    let mut objects = List::<Double>::new(values.len());
+    for (idx, x) in values.iter().enumerate() {
+        objects.set_at(idx, Double::new(*x as f64)).unwrap();
+    }
+    let typed = List::<Double>::from_handle(*List::<f32>::new_data(values.to_vec()))
+        .ok()
+        .unwrap();
+    for list in &[objects, typed] {
+        assert_eq!(list.sum(), 2.0);
+        assert_eq!(list.min(), Some(-2.5));
+        assert_eq!(list.max(), Some(4.0));
+    }
+}
+
+fn deep_clone_does_not_alias() {
+    let mut inner = List::<Integer>::new_integer(1);
+    inner.set_at(0, Integer::new(1)).unwrap();
+    let mut outer = List::<List<Integer>>::new(2);
+    outer.set_at(0, inner.clone()).unwrap();
+    outer.set_at(1, inner.clone()).unwrap();
+
+    let clone = outer.deep_clone();
+    assert!(!UnverifiedDartHandle::identity_eq(*clone, *outer));
+    let cloned_inner = clone.get_at(0).unwrap();
+    assert!(!UnverifiedDartHandle::identity_eq(*cloned_inner, *inner));
+    // Both elements referred to the same list, and still do.
+    assert!(UnverifiedDartHandle::identity_eq(
+        *cloned_inner,
+        *clone.get_at(1).unwrap()
+    ));
+
+    inner.set_at(0, Integer::new(2)).unwrap();
+    outer.set_at(1, List::<Integer>::new_integer(0)).unwrap();
+    assert_eq!(cloned_inner.get_at(0).unwrap().value(), 1);
+    assert_eq!(clone.get_at(1).unwrap().length(), 1);
+
+    let data = List::<i32>::new_data(vec![1, 2, 3]);
+    let data_clone = data.deep_clone();
+    assert!(!UnverifiedDartHandle::identity_eq(*data_clone, *data));
+    assert!(data_clone.is_typed_data());
+}
+
+fn ord_doubles_sort_nans_last() {
+    let mut values = [std::f64::NAN, 1.0, -0.0, 0.0, -1.0]
+        .iter()
+        .map(|&x| OrdDouble(Double::new(x)))
+        .collect::<Vec<_>>();
+    values.sort();
+    let sorted = values.iter().map(|x| x.value()).collect::<Vec<_>>();
+    assert_eq!(&sorted[..4], &[-1.0, -0.0, 0.0, 1.0]);
+    assert!(sorted[2].is_sign_positive() && sorted[1].is_sign_negative());
+    assert!(sorted[4].is_nan());
+}
+
+fn set_new_uses_element_type() {
+    let mut set = Set::<DString>::new().unwrap();
+    assert!(set.add(DString::new("a")).unwrap());
+    assert!(!set.add(DString::new("a")).unwrap());
+    assert!(set.contains(&DString::new("a")).unwrap());
+    assert_eq!(set.length(), 1);
+    // The type argument rejects elements of other types.
+    let added = set
+        .safe_handle()
+        .invoke(DString::new("add").safe_handle(), &mut [*Double::new(1.0)]);
+    assert!(added.is_err());
+}
+
+fn map_type_is_the_map_interface() {
+    let mut map = Map::<DString, Integer>::new().unwrap();
+    map.insert(DString::new("a"), Integer::new(1)).unwrap();
+    assert_eq!(map.get(DString::new("a")).unwrap().unwrap().value(), 1);
+    assert!(map.get(DString::new("b")).unwrap().is_none());
+    let is_map = <Map<DString, Integer> as DartType>::THIS.with(|ty| map.instanceof(*ty));
+    assert!(is_map.unwrap());
+    let mismatched = Map::<DString, Double>::from_handle(*map).ok().unwrap();
+    let error = mismatched.get(DString::new("a")).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::Api);
+}
+
+///
+/// Calls the Dart method `name` of `x`, to compare against.
+///
+fn dart_int(x: &Integer, name: &str, args: &mut [UnverifiedDartHandle]) -> Result<i64, Error> {
+    let result = x.invoke(UnverifiedDartHandle::string_from_str(name), args)?;
+    Ok(Integer::from_handle(result).ok().unwrap().value())
+}
+
+fn integer_bit_ops_match_dart() {
+    let values = [0, 1, -1, -5, 255, -12_345_678_901, i64::MIN, i64::MAX];
+    for &value in &values {
+        let x = Integer::new(value);
+        for &width in &[0u32, 1, 63, 64] {
+            let w = *Integer::from(width);
+            let unsigned = dart_int(&x, "toUnsigned", &mut [w]).unwrap();
+            assert_eq!(
+                x.to_unsigned(width).value(),
+                unsigned,
+                "{}.toUnsigned({})",
+                value,
+                width
+            );
+            let signed = dart_int(&x, "toSigned", &mut [w]).ok();
+            assert_eq!(
+                x.to_signed(width).ok().map(|x| x.value()),
+                signed,
+                "{}.toSigned({})",
+                value,
+                width
+            );
+        }
+        let bit_length = dart_int(&x, "bitLength", &mut []).unwrap();
+        assert_eq!(x.bit_length(), bit_length, "{}.bitLength", value);
+    }
+    assert_eq!(Integer::new(-1).to_unsigned(63).value(), i64::MAX);
+    assert_eq!(Integer::new(-1).to_unsigned(64).value(), -1);
+    assert_eq!(Integer::new(255).to_signed(8).unwrap().value(), -1);
+    assert!(Integer::new(5).to_signed(0).is_err());
+    assert_eq!(Integer::new(-1).bit_length(), 0);
+    assert_eq!(Integer::new(i64::MIN).bit_length(), 63);
+}
+
+fn integer_mod_pow_matches_dart() {
+    let cases = [
+        (-3, 5, 7),
+        (-2, 63, 1000),
+        (5, 0, 1),
+        (i64::MIN, 3, 97),
+        (i64::MAX, i64::MAX, i64::MAX - 1),
+        (2, -1, 5),
+        (2, 3, 0),
+        (2, 3, -5),
+    ];
+    for &(base, exponent, modulus) in &cases {
+        let x = Integer::new(base);
+        let expected = dart_int(
+            &x,
+            "modPow",
+            &mut [*Integer::new(exponent), *Integer::new(modulus)],
+        )
+        .ok();
+        let result = x.mod_pow(Integer::new(exponent), Integer::new(modulus));
+        assert_eq!(
+            result.ok().map(|x| x.value()),
+            expected,
+            "{}.modPow({}, {})",
+            base,
+            exponent,
+            modulus
+        );
+    }
+    assert_eq!(
+        Integer::new(-3)
+            .mod_pow(Integer::new(5), Integer::new(7))
+            .unwrap()
+            .value(),
+        2
+    );
+}
+
+fn from_handle_checked_rejects_mistyped_lists() {
+    let mut doubles = List::new_dynamic(2);
+    doubles.set_at(0, *Double::new(1.0)).unwrap();
+    doubles.set_at(1, *Double::new(2.0)).unwrap();
+    assert!(List::<Double>::from_handle_checked(*doubles).is_ok());
+    assert!(List::<Integer>::from_handle_checked(*doubles).is_err());
+    // Only the elements are checked, so an empty list passes.
+    let empty = List::<Double>::new(0);
+    assert!(List::<Integer>::from_handle_checked(*empty).is_ok());
+}
+
+fn from_chars_encodes_surrogate_pairs() {
+    let string = DString::from_chars("a😀".chars()).unwrap();
+    assert_eq!(string.length().value(), 3);
+    assert_eq!(string.code_units_vec(), vec![0x61, 0xD83D, 0xDE00]);
+}
+
+fn set_at_checked_rejects_mistyped_items() {
+    let mut ints = List::<Integer>::new_integer(1);
+    ints.set_at_checked(0, Integer::new(1)).unwrap();
+    let mut doubles = List::<Double>::from_handle(*ints).ok().unwrap();
+    assert!(doubles.set_at_checked(0, Double::new(1.0)).is_err());
+    assert_eq!(ints.get_at(0).unwrap().value(), 1);
+}
+
+fn weak_handles_are_cleared_by_gc() {
+    let weak = {
+        let _scope = unsafe { DartScope::enter() };
+        let object = List::<Integer>::new_integer(16);
+        WeakPersistentHandle::new(*object).unwrap()
+    };
+    assert!(weak.is_alive());
+    // Asks the VM for a full collection.
+    unsafe { dart_sys::Dart_NotifyLowMemory() };
+    assert!(!weak.is_alive());
+    assert!(weak.upgrade().is_none());
+}
+
+#[cfg(feature = "debug-hooks")]
+fn posting_arrays_frees_them() {
+    unsafe extern "C" fn ignore(_: dart_sys::Dart_Port, _: *mut dart_sys::Dart_CObject) {}
+    let name = std::ffi::CString::new("posting_arrays_frees_them").unwrap();
+    let native = unsafe { dart::dart_handle::NativePort::new_native(name, ignore) }.unwrap();
+    let port = unsafe { SendPort::from_port(native.port()) }.unwrap();
+    for idx in 0..100 {
+        let obj = CObject::Array(vec![
+            CObject::Int64(idx),
+            CObject::String(std::ffi::CString::new("leak").unwrap()),
+            CObject::Array(vec![CObject::Null]),
+        ]);
+        assert_eq!(port.post_cobject_counted(obj), (true, 7));
+    }
+    native.close();
+}
+
+#[cfg(feature = "debug-hooks")]
+fn scoped_hook_balances_scopes() {
+    use dart::dart_handle::scope_depth;
+    let depth = scope_depth();
+    for _ in 0..100 {
+        // Panicking within the hook would propagate an error, so the
+        // depth is checked outside of it. The arguments are never
+        // used, so they needn't be valid.
+        let inner = std::cell::Cell::new(0);
+        let inner_ref = std::panic::AssertUnwindSafe(&inner);
+        unsafe {
+            dart::catch_panic_hook_scoped(
+                move |_| {
+                    inner_ref.set(scope_depth());
+                    DString::new("scoped");
+                },
+                std::ptr::null_mut(),
+                "scoped",
+            );
+        }
+        assert_eq!(inner.get(), depth + 1);
+        assert_eq!(scope_depth(), depth);
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn call_hook_reports_duration() {
+    use std::sync::{Arc, Mutex};
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let recorded = calls.clone();
+    dart::metrics::set_call_hook(Some(Box::new(move |name, duration| {
+        recorded.lock().unwrap().push((name.to_string(), duration));
+    })));
+    // The arguments are never used, so they needn't be valid.
+    unsafe {
+        dart::catch_panic_hook(
+            |_| std::thread::sleep(Duration::from_millis(20)),
+            std::ptr::null_mut(),
+            "sleepy",
+        );
+    }
+    dart::metrics::set_call_hook(None);
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "sleepy");
+    assert!(calls[0].1 >= Duration::from_millis(20));
+    assert!(calls[0].1 < Duration::from_secs(10));
+}
+
+thread_local! {
+    static FUTURE_PORTS: RefCell<Vec<dart_sys::Dart_Port>> = RefCell::new(Vec::new());
+}
+
+static FUTURE_VALUE: AtomicI64 = AtomicI64::new(i64::MIN);
+static FUTURE_CAUGHT: AtomicI64 = AtomicI64::new(i64::MIN);
+
+unsafe extern "C" fn store_value(_: dart_sys::Dart_Port, message: *mut dart_sys::Dart_CObject) {
+    match CObject::from(*message) {
+        CObject::Int32(x) => FUTURE_VALUE.store(x.into(), Ordering::SeqCst),
+        CObject::Int64(x) => FUTURE_VALUE.store(x, Ordering::SeqCst),
+        _ => {}
+    }
+}
+
+unsafe extern "C" fn store_caught(_: dart_sys::Dart_Port, message: *mut dart_sys::Dart_CObject) {
+    if let CObject::Bool(x) = CObject::from(*message) {
+        FUTURE_CAUGHT.store(x.into(), Ordering::SeqCst);
+    }
+}
+
+///
+/// Creates a future using the named constructor `name` of `Future`,
+/// such as `Future.value`.
+///
+fn dart_future(name: &str, arg: UnverifiedDartHandle) -> Future {
+    let class = DartClass::new("dart:async", "Future").unwrap();
+    let future = class
+        .new_of_type_self(Some(*DString::new(name)), &mut [arg])
+        .unwrap();
+    Future::from_handle(future).ok().unwrap()
+}
+
+fn complete_with_port(
+    future: Future,
+    name: &str,
+    handler: unsafe extern "C" fn(dart_sys::Dart_Port, *mut dart_sys::Dart_CObject),
+) {
+    let name = CString::new(name).unwrap();
+    let port = unsafe { future.complete_with_port(name, handler) }.unwrap();
+    FUTURE_PORTS.with(|ports| ports.borrow_mut().push(port.port()));
+}
+
+fn start_future_completes_through_port() {
+    // `10.remainder` is called with the value of the future.
+    let remainder = Integer::new(10).get_field(*DString::new("remainder"));
+    let remainder = Closure::from_handle(remainder.unwrap()).ok().unwrap();
+    let future = dart_future("value", *Integer::new(7)).then(remainder);
+    complete_with_port(future.unwrap(), "future_value", store_value);
+}
+
+fn check_future_completes_through_port() {
+    assert_eq!(FUTURE_VALUE.load(Ordering::SeqCst), 3);
+}
+
+fn start_failed_future_is_caught() {
+    // `identical` is called with the error and its stack trace.
+    let core = UnverifiedDartHandle::lookup_library(*DString::new("dart:core")).unwrap();
+    let identical = core.get_field(*DString::new("identical")).unwrap();
+    let identical = Closure::from_handle(identical).ok().unwrap();
+    let future = dart_future("error", *DString::new("boom")).catch_error(identical);
+    complete_with_port(future.unwrap(), "future_caught", store_caught);
+}
+
+fn check_failed_future_is_caught() {
+    assert_eq!(FUTURE_CAUGHT.load(Ordering::SeqCst), 0);
+}
+
+///
+/// Runs each test in its own scope, returning the failures as a list
+/// of messages.
+///
+fn run_all<'a>(tests: impl Iterator<Item = (&'a str, fn())>) -> UnverifiedDartHandle {
+    let mut failures = Vec::new();
+    for (name, test) in tests {
+        let result = catch_unwind(|| {
+            let _scope = unsafe { DartScope::enter() };
+            test()
+        });
+        if result.is_err() {
+            failures.push(format!("Test `{}` failed", name));
+        }
+    }
+    let mut list = List::new_dynamic(failures.len());
+    for (idx, failure) in failures.iter().enumerate() {
+        list.set_at(idx, *DString::new(failure)).unwrap();
+    }
+    *list
+}
+
+fn run_tests(arguments: NativeArguments) {
+    let started = ASYNC_TESTS.iter().map(|&(name, start, _)| (name, start));
+    arguments.set_return(run_all(TESTS.iter().copied().chain(started)));
+}
+
+fn check_async_tests(arguments: NativeArguments) {
+    let checks = ASYNC_TESTS.iter().map(|&(name, _, check)| (name, check));
+    arguments.set_return(run_all(checks));
+    FUTURE_PORTS.with(|ports| {
+        for port in ports.borrow_mut().drain(..) {
+            unsafe { dart_sys::Dart_CloseNativePort(port) };
+        }
+    });
+}
+
+export_dart_functions!(test_exports: ["runTests" -> run_tests], ["checkAsyncTests" -> check_async_tests]);
+create_init_function!(vm_tests, [test_exports]);
@@ -1,4 +1,6 @@
-use crate::dart_handle::{Port, TypedData};
+use crate::dart_handle::{
+    typed_data_type_name, Error, SendPort, TypedData, TypedDataGuard, UnverifiedDartHandle,
+};
 use dart_sys as ffi;
 use std::any::Any;
 use std::ffi::{CStr, CString};
@@ -19,6 +21,26 @@ pub enum CObject {
 }
 
 impl CObject {
+    ///
+    /// Converts a raw `CObject`, such as a message received by a native
+    /// port, into a `CObject`. Strings and arrays are copied, however
+    /// typed data is not.
+    ///
+    /// # Ownership of typed data
+    /// The buffers of typed data are borrowed from `object`, and so are
+    /// only valid for as long as it is. For a received message, this is
+    /// until the port's handler returns.
+    ///
+    /// External typed data is owned by whoever attached its finalizer
+    /// (usually the sender), and is therefore converted into typed data
+    /// without a finalizer. Posting it again copies the buffer instead
+    /// of handing the same finalizer to the VM a second time, which
+    /// would free the buffer twice.
+    ///
+    /// # Safety
+    /// `object` must be a valid `CObject`, with valid pointers for its
+    /// strings, arrays and typed data.
+    ///
     pub unsafe fn from(ffi::Dart_CObject { type_: ty, value }: ffi::Dart_CObject) -> Self {
         use ffi::Dart_CObject_Type::*;
         match ty {
@@ -43,7 +65,12 @@ impl CObject {
             }
             TypedData => CObject::TypedData(TypedDataArray::new(value.as_typed_data)),
             ExternalTypedData => {
-                CObject::TypedData(TypedDataArray::new_external(value.as_external_typed_data))
+                let data = value.as_external_typed_data;
+                CObject::TypedData(TypedDataArray::new(ffi::Dart_TypedData {
+                    type_: data.type_,
+                    length: data.length,
+                    values: data.data,
+                }))
             }
             Unsupported => panic!("Unsupported CObject!"),
             NumberOfTypes => unimplemented!("Number of Typed has yet to be implemented!"),
@@ -53,6 +80,131 @@ impl CObject {
         }
     }
 
+    ///
+    /// Converts a Dart object into a `CObject`, copying its contents.
+    ///
+    /// `null`, `bool`s, `int`s which fit in 64 bits, `double`s, `String`s
+    /// and `SendPort`s are supported, as well as typed data (other than
+    /// `ByteData`, `Uint8ClampedList` and `Float32x4List`) and lists of
+    /// the above, which are converted recursively. Anything else returns
+    /// an error.
+    ///
+    pub fn from_handle(handle: UnverifiedDartHandle) -> Result<Self, Error> {
+        let handle = handle.get_error()?;
+        if handle.is_null() {
+            Ok(CObject::Null)
+        } else if handle.is_boolean() {
+            Ok(CObject::Bool(handle.get_bool()?))
+        } else if handle.is_integer() {
+            Ok(CObject::Int64(handle.get_i64()?))
+        } else if handle.is_double() {
+            Ok(CObject::Double(handle.get_f64()?))
+        } else if handle.is_string() {
+            Ok(CObject::String(handle.string_to_cstring()?))
+        } else if handle.is_typed_data() {
+            Self::from_typed_data(handle)
+        } else if handle.is_list() {
+            let len = handle.list_length()?;
+            let mut items = Vec::with_capacity(len);
+            for idx in 0..len {
+                items.push(Self::from_handle(handle.list_at(idx)?)?);
+            }
+            Ok(CObject::Array(items))
+        } else if let Ok(port) = SendPort::from_send_port(handle) {
+            Ok(CObject::SendPort(Sender(ffi::Dart_SendPort {
+                id: port.id(),
+                origin_id: ffi::ILLEGAL_PORT,
+            })))
+        } else {
+            Err(Error::new_api("Object cannot be converted to a CObject.").unwrap())
+        }
+    }
+
+    fn from_typed_data(handle: UnverifiedDartHandle) -> Result<Self, Error> {
+        fn copy<T: TypedData>(handle: UnverifiedDartHandle) -> Result<CObject, Error> {
+            let data = TypedDataGuard::<T>::acquire(&handle)?.map(|x| x.to_vec());
+            match data {
+                Some(x) => Ok(CObject::TypedData(TypedDataArray::create(x).recast())),
+                None => Err(Error::new_api("Mismatched typed data type.").unwrap()),
+            }
+        }
+        use ffi::Dart_TypedData_Type::*;
+        match handle.typed_data_get_type() {
+            Int8 => copy::<i8>(handle),
+            Uint8 => copy::<u8>(handle),
+            Int16 => copy::<i16>(handle),
+            Uint16 => copy::<u16>(handle),
+            Int32 => copy::<i32>(handle),
+            Uint32 => copy::<u32>(handle),
+            Int64 => copy::<i64>(handle),
+            Uint64 => copy::<u64>(handle),
+            Float32 => copy::<f32>(handle),
+            Float64 => copy::<f64>(handle),
+            ty => Err(Error::new_api(&format!(
+                "A {} cannot be converted to a CObject.",
+                typed_data_type_name(ty)
+            ))
+            .unwrap()),
+        }
+    }
+
+    ///
+    /// Creates a Dart object with the contents of this `CObject`, the
+    /// inverse of [`from_handle`](CObject::from_handle). Arrays are
+    /// converted into new `List<dynamic>`s recursively, and typed data
+    /// is copied into new typed data of the same type.
+    ///
+    pub fn to_handle(&self) -> Result<UnverifiedDartHandle, Error> {
+        match self {
+            CObject::Null => Ok(UnverifiedDartHandle::null()),
+            CObject::Bool(x) => Ok(UnverifiedDartHandle::new_bool(*x)),
+            CObject::Int32(x) => Ok(UnverifiedDartHandle::new_i64(*x as i64)),
+            CObject::Int64(x) => Ok(UnverifiedDartHandle::new_i64(*x)),
+            CObject::Double(x) => Ok(UnverifiedDartHandle::new_f64(*x)),
+            CObject::String(x) => UnverifiedDartHandle::string_from_cstr(x).get_error(),
+            CObject::SendPort(Sender(x)) => unsafe {
+                UnverifiedDartHandle::new(ffi::Dart_NewSendPort(x.id)).get_error()
+            },
+            CObject::Array(items) => {
+                let list = UnverifiedDartHandle::new_list(items.len())?;
+                for (idx, item) in items.iter().enumerate() {
+                    list.list_set_at(item.to_handle()?, idx)?;
+                }
+                Ok(list)
+            }
+            CObject::TypedData(x) => Self::to_typed_data(x),
+        }
+    }
+
+    fn to_typed_data(array: &TypedDataArray<dyn Any>) -> Result<UnverifiedDartHandle, Error> {
+        let (ty, values, len) = match array {
+            TypedDataArray::WithoutFinalizer(x, _) => (x.type_, x.values, x.length as usize),
+            TypedDataArray::WithFinalizer(x) => (x.type_, x.data, x.length as usize),
+        };
+        let size = typed_data_element_size(ty).ok_or_else(|| {
+            Error::new_api(&format!("Typed data has an invalid element type {:?}.", ty)).unwrap()
+        })?;
+        let handle = UnverifiedDartHandle::new_typed_data(ty, len)?;
+        if len == 0 {
+            return Ok(handle);
+        }
+        unsafe {
+            let mut acquired_ty = ty;
+            let mut data = std::ptr::null_mut();
+            let mut acquired_len = 0;
+            UnverifiedDartHandle::new(ffi::Dart_TypedDataAcquireData(
+                *handle,
+                &mut acquired_ty,
+                &mut data,
+                &mut acquired_len,
+            ))
+            .get_error()?;
+            std::ptr::copy_nonoverlapping(values as *const u8, data as *mut u8, len * size);
+            UnverifiedDartHandle::new(ffi::Dart_TypedDataReleaseData(*handle)).get_error()?;
+        }
+        Ok(handle)
+    }
+
     pub fn into_leak(self) -> ffi::Dart_CObject {
         use dart_sys::Dart_CObjectValue;
         match self {
@@ -119,9 +271,61 @@ impl CObject {
         }
     }
 
+    ///
+    /// Frees the strings and arrays allocated by
+    /// [`into_leak`](CObject::into_leak), returning the number of
+    /// allocations which were freed. Typed data is left untouched, as
+    /// it is never owned by a `CObject`.
+    ///
+    /// # Safety
+    /// `object` must have been created by `into_leak`, and must not be
+    /// used again afterwards.
+    ///
+    pub(crate) unsafe fn free_leaked(object: ffi::Dart_CObject) -> usize {
+        Self::free_raw(object, true)
+    }
+
+    ///
+    /// Frees the arrays of a raw `CObject`, as well as its strings
+    /// should `owns_strings` be `true`.
+    ///
+    unsafe fn free_raw(object: ffi::Dart_CObject, owns_strings: bool) -> usize {
+        match object.type_ {
+            ffi::Dart_CObject_Type::String if owns_strings => {
+                drop(CString::from_raw(object.value.as_string));
+                1
+            }
+            ffi::Dart_CObject_Type::Array => {
+                let array = object.value.as_array;
+                let values = Box::from_raw(std::slice::from_raw_parts_mut(
+                    array.values as *mut Box<ffi::Dart_CObject>,
+                    array.length as usize,
+                ));
+                let mut freed = 1;
+                for value in values.into_vec() {
+                    freed += 1 + Self::free_raw(*value, owns_strings);
+                }
+                freed
+            }
+            _ => 0,
+        }
+    }
+
+    ///
+    /// Creates a raw `CObject` borrowing the contents of this one,
+    /// which can be posted without giving up ownership of them.
+    ///
     pub fn as_non_leak(&'_ self) -> CObjectLock<'_> {
+        unsafe { CObjectLock::new(self, self.as_raw_borrowed()) }
+    }
+
+    ///
+    /// Creates a raw `CObject` borrowing the contents of this one. The
+    /// arrays it allocates must be freed using `free_raw`.
+    ///
+    fn as_raw_borrowed(&self) -> ffi::Dart_CObject {
         use dart_sys::Dart_CObjectValue;
-        let obj = match self {
+        match self {
             CObject::Null => ffi::Dart_CObject {
                 type_: ffi::Dart_CObject_Type::Null,
                 value: Dart_CObjectValue { as_bool: false },
@@ -160,8 +364,8 @@ impl CObject {
             },
             CObject::Array(x) => {
                 let vec: Vec<Box<ffi::Dart_CObject>> = x
-                    .into_iter()
-                    .map(|x| x.as_non_leak().object)
+                    .iter()
+                    .map(|x| x.as_raw_borrowed())
                     .map(Box::new)
                     .collect();
                 let boxed = Box::leak(vec.into_boxed_slice());
@@ -185,18 +389,139 @@ impl CObject {
                     value: Dart_CObjectValue { as_string: ptr },
                 }
             }
-        };
+        }
+    }
 
-        unsafe { CObjectLock::new(self, obj) }
+    ///
+    /// Checks that this object can be posted, returning the first
+    /// problem found otherwise. This is much cheaper than posting, and
+    /// gives a description of what is wrong rather than just `false`.
+    ///
+    /// Strings are stored as [`CString`]s and so can never contain an
+    /// interior nul byte; those are reported when creating the
+    /// `CString` instead.
+    ///
+    pub fn validate(&self) -> Result<(), CObjectError> {
+        self.validate_at(0)
+    }
+
+    fn validate_at(&self, depth: usize) -> Result<(), CObjectError> {
+        match self {
+            CObject::Array(x) => {
+                if depth >= MAX_COBJECT_DEPTH {
+                    return Err(CObjectError::TooDeep);
+                }
+                if x.len() > isize::MAX as usize {
+                    return Err(CObjectError::ArrayTooLong(x.len()));
+                }
+                x.iter().try_for_each(|x| x.validate_at(depth + 1))
+            }
+            CObject::TypedData(x) => {
+                let (ty, length, data) = match x {
+                    TypedDataArray::WithoutFinalizer(x, _) => (x.type_, x.length, x.values),
+                    TypedDataArray::WithFinalizer(x) => (x.type_, x.length, x.data),
+                };
+                let size = match typed_data_element_size(ty) {
+                    Some(x) => x,
+                    None => return Err(CObjectError::InvalidTypedDataType(ty)),
+                };
+                if length < 0 || length.checked_mul(size as isize).is_none() {
+                    return Err(CObjectError::TypedDataTooLong(length));
+                }
+                if length > 0 && data.is_null() {
+                    return Err(CObjectError::NullTypedData);
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+///
+/// The deepest nesting of [`CObject::Array`]s which
+/// [`CObject::validate`] accepts.
+///
+pub const MAX_COBJECT_DEPTH: usize = 512;
+
+fn typed_data_element_size(ty: ffi::Dart_TypedData_Type) -> Option<usize> {
+    use ffi::Dart_TypedData_Type::*;
+    match ty {
+        ByteData | Int8 | Uint8 | Uint8Clamped => Some(1),
+        Int16 | Uint16 => Some(2),
+        Int32 | Uint32 | Float32 => Some(4),
+        Int64 | Uint64 | Float64 => Some(8),
+        Float32x4 => Some(16),
+        _ => None,
     }
 }
 
+///
+/// A reason for a [`CObject`] not being postable, as found by
+/// [`CObject::validate`].
+///
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CObjectError {
+    ///
+    /// Arrays are nested more than [`MAX_COBJECT_DEPTH`] deep.
+    ///
+    TooDeep,
+    ///
+    /// An array has more elements than its length, an `isize`, can
+    /// hold.
+    ///
+    ArrayTooLong(usize),
+    ///
+    /// Typed data has a negative length, or one whose size in bytes
+    /// overflows an `isize`.
+    ///
+    TypedDataTooLong(isize),
+    ///
+    /// Typed data has an element type which can't be posted, such as
+    /// [`Invalid`](ffi::Dart_TypedData_Type::Invalid).
+    ///
+    InvalidTypedDataType(ffi::Dart_TypedData_Type),
+    ///
+    /// Typed data with a non-zero length has a null data pointer.
+    ///
+    NullTypedData,
+}
+
+impl std::fmt::Display for CObjectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CObjectError::TooDeep => write!(
+                f,
+                "Arrays are nested more than {} levels deep",
+                MAX_COBJECT_DEPTH
+            ),
+            CObjectError::ArrayTooLong(x) => write!(f, "Array of length {} is too long", x),
+            CObjectError::TypedDataTooLong(x) => {
+                write!(f, "Typed data of length {} is too long", x)
+            }
+            CObjectError::InvalidTypedDataType(x) => {
+                write!(f, "Typed data has an invalid element type {:?}", x)
+            }
+            CObjectError::NullTypedData => write!(f, "Typed data has a null data pointer"),
+        }
+    }
+}
+
+impl std::error::Error for CObjectError {}
+
 pub struct CObjectLock<'a> {
     _rust_cobject: &'a CObject,
     pub(crate) object: ffi::Dart_CObject,
 }
 
 impl<'a> CObjectLock<'a> {
+    ///
+    /// # Safety
+    /// `object` must borrow the contents of `rust_cobject`, with its
+    /// arrays allocated the same way as by
+    /// [`as_non_leak`](CObject::as_non_leak), as they are freed once
+    /// the lock is dropped.
+    ///
     pub unsafe fn new(rust_cobject: &'a CObject, object: ffi::Dart_CObject) -> Self {
         Self {
             _rust_cobject: rust_cobject,
@@ -205,7 +530,7 @@ impl<'a> CObjectLock<'a> {
     }
     pub fn post_onto(&mut self, sender: &mut Sender) -> bool {
         unsafe {
-            let port = Port::from_port(sender.0.id);
+            let port = SendPort::from_port(sender.0.id);
             if let Some(port) = port {
                 port.post_raw_cobject(&mut self.object)
             } else {
@@ -215,9 +540,27 @@ impl<'a> CObjectLock<'a> {
     }
 }
 
+impl<'a> Drop for CObjectLock<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            CObject::free_raw(self.object, false);
+        }
+    }
+}
+
 #[repr(transparent)]
 pub struct Sender(pub ffi::Dart_SendPort);
 
+///
+/// The contents of typed data within a [`CObject`].
+///
+/// - `WithoutFinalizer` borrows its buffer, which is copied when it is
+///   posted.
+/// - `WithFinalizer` owns its buffer, which is handed over to the VM
+///   along with the finalizer once it is posted, after which it must
+///   not be used. As this type is `Copy`, take care to post any copy
+///   of it at most once.
+///
 #[derive(Copy, Clone)]
 pub enum TypedDataArray<T: ?Sized> {
     WithoutFinalizer(ffi::Dart_TypedData, PhantomData<T>),
@@ -280,6 +623,18 @@ impl<T: TypedData> TypedDataArray<T> {
         })
     }
 
+    ///
+    /// Creates an array holding a copy of `data`, leaving the caller
+    /// with ownership of the original slice.
+    ///
+    /// This allocates and copies the entire slice once, after which it
+    /// behaves as [`create`](TypedDataArray::create) does. Prefer
+    /// `create` when the data can be handed over to the VM.
+    ///
+    pub fn from_slice_copied(data: &[T]) -> Self {
+        Self::create(data.to_vec())
+    }
+
     pub fn recast(self) -> TypedDataArray<dyn Any> {
         match self {
             TypedDataArray::WithFinalizer(x) => unsafe { TypedDataArray::new_external(x) },
@@ -288,37 +643,309 @@ impl<T: TypedData> TypedDataArray<T> {
     }
 }
 
+impl<T: TypedData> TypedDataArray<T> {
+    fn raw_parts(&self) -> (ffi::Dart_TypedData_Type, *mut T, usize) {
+        match self {
+            TypedDataArray::WithoutFinalizer(x, _) => (x.type_, x.values as *mut T, x.length as _),
+            TypedDataArray::WithFinalizer(x) => (x.type_, x.data as *mut T, x.length as _),
+        }
+    }
+
+    ///
+    /// Views the elements of the array.
+    ///
+    /// # Panics
+    /// Panics should the array's element type not be `T`, which can
+    /// happen should this have been constructed directly from mismatched
+    /// ffi data rather than through [`cast`](TypedDataArray::cast).
+    ///
+    pub fn as_slice(&self) -> &[T] {
+        let (ty, ptr, len) = self.raw_parts();
+        assert_eq!(ty, T::TYPE, "TypedDataArray's element type does not match its data");
+        if len == 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(ptr, len) }
+    }
+
+    ///
+    /// Mutably views the elements of the array. See
+    /// [`as_slice`](TypedDataArray::as_slice) for when this panics.
+    ///
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let (ty, ptr, len) = self.raw_parts();
+        assert_eq!(ty, T::TYPE, "TypedDataArray's element type does not match its data");
+        if len == 0 {
+            return &mut [];
+        }
+        unsafe { std::slice::from_raw_parts_mut(ptr, len) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.raw_parts().2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.as_slice().get(idx)
+    }
+
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(idx)
+    }
+}
+
 impl<T: TypedData + Sized> Index<usize> for TypedDataArray<T> {
     type Output = T;
     fn index(&self, idx: usize) -> &T {
-        use TypedDataArray::*;
-        match self {
-            WithoutFinalizer(ffi::Dart_TypedData { length, values, .. }, _)
-            | WithFinalizer(ffi::Dart_ExternalTypedData {
-                length,
-                data: values,
-                ..
-            }) => unsafe {
-                let slice = std::slice::from_raw_parts(*values as *mut T, *length as _);
-                &slice[idx]
-            },
-        }
+        let len = self.len();
+        self.get(idx).unwrap_or_else(|| {
+            panic!("Index {} out of range for TypedDataArray of length {}", idx, len)
+        })
     }
 }
 
 impl<T: TypedData + Sized> IndexMut<usize> for TypedDataArray<T> {
     fn index_mut(&mut self, idx: usize) -> &mut T {
-        use TypedDataArray::*;
-        match self {
-            WithoutFinalizer(ffi::Dart_TypedData { length, values, .. }, _)
-            | WithFinalizer(ffi::Dart_ExternalTypedData {
-                length,
-                data: values,
-                ..
-            }) => unsafe {
-                let slice = std::slice::from_raw_parts_mut(*values as *mut T, *length as _);
-                &mut slice[idx]
+        let len = self.len();
+        self.get_mut(idx).unwrap_or_else(|| {
+            panic!("Index {} out of range for TypedDataArray of length {}", idx, len)
+        })
+    }
+}
+
+///
+/// A message tagged with an integer, for protocols which send
+/// several kinds of messages over a single port.
+///
+/// This is laid out as a two element [`CObject::Array`] where
+/// the first element is the tag and the second is the payload:
+/// `[tag, payload]`.
+///
+pub struct TaggedMessage {
+    pub tag: i64,
+    pub payload: CObject,
+}
+
+impl TaggedMessage {
+    pub fn new(tag: i64, payload: CObject) -> Self {
+        Self { tag, payload }
+    }
+
+    pub fn to_cobject(self) -> CObject {
+        CObject::Array(vec![CObject::Int64(self.tag), self.payload])
+    }
+
+    ///
+    /// Reads a tagged message out of a `CObject`, returning the
+    /// object back should it not be laid out as `[tag, payload]`.
+    ///
+    /// The tag may be either an `Int32` or `Int64`, since the VM
+    /// will send small integers as `Int32`s.
+    ///
+    pub fn from_cobject(obj: CObject) -> Result<Self, CObject> {
+        match obj {
+            CObject::Array(mut items) if items.len() == 2 => {
+                let tag = match items[0] {
+                    CObject::Int32(x) => x as i64,
+                    CObject::Int64(x) => x,
+                    _ => return Err(CObject::Array(items)),
+                };
+                let payload = items.pop().unwrap();
+                Ok(Self { tag, payload })
+            }
+            x => Err(x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CObject, CObjectError, TaggedMessage, TypedDataArray, MAX_COBJECT_DEPTH};
+    use dart_sys as ffi;
+    use std::any::Any;
+    use std::ffi::CString;
+    use std::marker::PhantomData;
+
+    fn payload() -> CObject {
+        CObject::Array(vec![
+            CObject::String(CString::new("payload").unwrap()),
+            CObject::Double(1.5),
+        ])
+    }
+
+    fn assert_payload(obj: &CObject) {
+        match obj {
+            CObject::Array(items) => match &items[..] {
+                [CObject::String(s), CObject::Double(x)] => {
+                    assert_eq!(s.to_str().unwrap(), "payload");
+                    assert_eq!(*x, 1.5);
+                }
+                _ => panic!("Payload has the wrong elements"),
             },
+            _ => panic!("Payload is not an array"),
         }
     }
+
+    #[test]
+    fn tagged_message_round_trips() {
+        let obj = TaggedMessage::new(-7, payload()).to_cobject();
+        let message = TaggedMessage::from_cobject(obj).ok().unwrap();
+        assert_eq!(message.tag, -7);
+        assert_payload(&message.payload);
+    }
+
+    #[test]
+    fn tagged_message_round_trips_through_raw() {
+        let raw = TaggedMessage::new(i64::MAX, payload()).to_cobject().into_leak();
+        let obj = unsafe { CObject::from(raw) };
+        unsafe { CObject::free_leaked(raw) };
+        let message = TaggedMessage::from_cobject(obj).ok().unwrap();
+        assert_eq!(message.tag, i64::MAX);
+        assert_payload(&message.payload);
+    }
+
+    #[test]
+    fn tagged_message_accepts_int32_tags() {
+        let obj = CObject::Array(vec![CObject::Int32(3), CObject::Null]);
+        let message = TaggedMessage::from_cobject(obj).ok().unwrap();
+        assert_eq!(message.tag, 3);
+        assert!(matches!(message.payload, CObject::Null));
+    }
+
+    #[test]
+    fn untagged_objects_are_returned() {
+        let obj = CObject::Array(vec![CObject::Null, CObject::Null]);
+        match TaggedMessage::from_cobject(obj) {
+            Err(CObject::Array(items)) => assert_eq!(items.len(), 2),
+            _ => panic!("A message with a null tag was accepted"),
+        }
+        let obj = CObject::Array(vec![CObject::Int64(1)]);
+        assert!(TaggedMessage::from_cobject(obj).is_err());
+        assert!(matches!(
+            TaggedMessage::from_cobject(CObject::Int64(1)),
+            Err(CObject::Int64(1))
+        ));
+    }
+
+    fn nested(depth: usize) -> CObject {
+        (0..depth).fold(CObject::Null, |obj, _| CObject::Array(vec![obj]))
+    }
+
+    fn typed_data(type_: ffi::Dart_TypedData_Type, length: isize, values: *mut u8) -> CObject {
+        let data = ffi::Dart_TypedData {
+            type_,
+            length,
+            values,
+        };
+        CObject::TypedData(unsafe { TypedDataArray::new(data) })
+    }
+
+    #[test]
+    fn strings_cannot_hold_nul() {
+        assert!(CString::new("a\0b").is_err());
+        let obj = CObject::String(CString::new("ab").unwrap());
+        assert_eq!(obj.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_deep_nesting() {
+        assert_eq!(nested(MAX_COBJECT_DEPTH).validate(), Ok(()));
+        assert_eq!(
+            nested(MAX_COBJECT_DEPTH + 1).validate(),
+            Err(CObjectError::TooDeep)
+        );
+    }
+
+    #[test]
+    fn validate_rejects_bad_typed_data() {
+        use ffi::Dart_TypedData_Type::*;
+        let mut byte = 0u8;
+        assert_eq!(typed_data(Uint8, 1, &mut byte).validate(), Ok(()));
+        assert_eq!(
+            typed_data(Invalid, 1, &mut byte).validate(),
+            Err(CObjectError::InvalidTypedDataType(Invalid))
+        );
+        assert_eq!(
+            typed_data(Int32, -1, &mut byte).validate(),
+            Err(CObjectError::TypedDataTooLong(-1))
+        );
+        assert_eq!(
+            typed_data(Float64, isize::MAX, &mut byte).validate(),
+            Err(CObjectError::TypedDataTooLong(isize::MAX))
+        );
+        assert_eq!(
+            typed_data(Uint8, 1, std::ptr::null_mut()).validate(),
+            Err(CObjectError::NullTypedData)
+        );
+        assert_eq!(typed_data(Uint8, 0, std::ptr::null_mut()).validate(), Ok(()));
+    }
+
+    #[test]
+    fn free_leaked_counts_every_allocation() {
+        // The outer array, its three boxed elements, the string and
+        // the inner array along with its boxed element.
+        for _ in 0..100 {
+            let obj = CObject::Array(vec![
+                CObject::Int64(1),
+                CObject::String(CString::new("leak").unwrap()),
+                CObject::Array(vec![CObject::Null]),
+            ]);
+            assert_eq!(unsafe { CObject::free_leaked(obj.into_leak()) }, 7);
+        }
+        assert_eq!(unsafe { CObject::free_leaked(CObject::Int64(1).into_leak()) }, 0);
+    }
+
+    ///
+    /// Borrows `data` as typed data of type `type_`, regardless of
+    /// whether it matches the element type `T`.
+    ///
+    fn borrowed<T: ?Sized, U>(type_: ffi::Dart_TypedData_Type, data: &mut [U]) -> TypedDataArray<T> {
+        let data = ffi::Dart_TypedData {
+            type_,
+            length: data.len() as isize,
+            values: data.as_mut_ptr() as *mut u8,
+        };
+        TypedDataArray::WithoutFinalizer(data, PhantomData)
+    }
+
+    #[test]
+    fn typed_data_get_is_checked() {
+        let mut data = [1i32, -2, 3];
+        let mut array = borrowed::<i32, _>(ffi::Dart_TypedData_Type::Int32, &mut data);
+        assert_eq!(array.get(1), Some(&-2));
+        assert_eq!(array.get(3), None);
+        *array.get_mut(2).unwrap() = 4;
+        assert_eq!(array.get_mut(3), None);
+        assert_eq!(array[2], 4);
+        assert_eq!(array.as_slice(), &[1, -2, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 3 out of range for TypedDataArray of length 3")]
+    fn typed_data_index_out_of_range() {
+        let mut data = [1u8, 2, 3];
+        let array = borrowed::<u8, _>(ffi::Dart_TypedData_Type::Uint8, &mut data);
+        let _ = array[3];
+    }
+
+    #[test]
+    fn typed_data_cast_checks_type() {
+        let mut data = [1u8, 2, 3, 4];
+        let mut array = || borrowed::<dyn Any, _>(ffi::Dart_TypedData_Type::Uint8, &mut data);
+        assert!(array().cast::<i32>().is_none());
+        assert!(array().cast::<i8>().is_none());
+        assert_eq!(array().cast::<u8>().unwrap().as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "element type does not match")]
+    fn typed_data_type_mismatch() {
+        let mut data = [1u8, 2, 3, 4];
+        let array = borrowed::<i32, _>(ffi::Dart_TypedData_Type::Uint8, &mut data);
+        array.get(0);
+    }
 }
@@ -18,12 +18,19 @@
 
 use dart_sys as ffi;
 use dart_sys::Dart_CObject;
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
 use std::convert::{Infallible, TryInto};
 use std::ffi::{CStr, CString, NulError};
 use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
-use std::ops::Deref;
+use std::ops::{Deref, DerefMut};
 use std::os::raw::{c_char, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 ///
 /// Describes a smart wrapper around a dart handle. This is
@@ -58,6 +65,13 @@ pub unsafe trait DartHandle: 'static + Sized {
     /// not be the correct handle type.
     ///
     fn from_handle(handle: UnverifiedDartHandle) -> Result<Self, UnverifiedDartHandle>;
+    ///
+    /// Gets the handle as a [`Dynamic`](crate::dart_types::dynamic::Dynamic),
+    /// to access it using the dynamic api.
+    ///
+    fn to_dynamic(&self) -> crate::dart_types::dynamic::Dynamic {
+        crate::dart_types::dynamic::Dynamic::from(self.safe_handle())
+    }
 }
 
 ///
@@ -82,6 +96,25 @@ pub unsafe trait DartHandle: 'static + Sized {
 /// makes it impossible to communicate handles between function entries,
 /// barring the use of `unsafe` with precise coordination to ensure that
 /// the handle is not invalidated.
+/// - Every wrapper holding a handle (the types in
+/// [`dart_types`](crate::dart_types), [`Error`], [`NativeArguments`](crate::dart_native_arguments::NativeArguments),
+/// etc.) as well as the scope guards [`DartScope`], [`IsolateScope`] and
+/// [`TypedDataGuard`] are neither `Send` nor `Sync` either. Only ports
+/// ([`Port`], [`SendPort`]) may be shared between threads.
+///   ```compile_fail
+///   use dart::prelude::*;
+///   fn assert_send<T: Send>() {}
+///   assert_send::<DString>();
+///   ```
+///   ```compile_fail
+///   use dart::prelude::*;
+///   fn assert_sync<T: Sync>() {}
+///   assert_sync::<List<Integer>>();
+///   ```
+///   ```compile_fail
+///   fn assert_send<T: Send>() {}
+///   assert_send::<dart::dart_handle::DartScope>();
+///   ```
 /// - Creating an `UnverifiedDartHandle` with garbage data is UB.
 /// This may cause the VM to try to dereference it and therefore invoke
 /// undefined behaviour and potentially crash the program in unexpected
@@ -177,6 +210,26 @@ impl UnverifiedDartHandle {
         }
     }
 
+    ///
+    /// Encodes `self` as JSON by calling `jsonEncode` from `dart:convert`,
+    /// returning the thrown error should `self` not be encodable.
+    ///
+    pub fn to_json_string(&self) -> Result<String, Error> {
+        thread_local! {
+            #[allow(non_upper_case_globals)]
+            static JsonEncode: IsolateCache<()> = IsolateCache::new();
+        }
+        let json_encode = JsonEncode.with(|x| {
+            x.get_or_try_insert_with((), || {
+                let library = UnverifiedDartHandle::lookup_library(
+                    UnverifiedDartHandle::string_from_str("dart:convert"),
+                )?;
+                library.get_field(UnverifiedDartHandle::string_from_str("jsonEncode"))
+            })
+        })?;
+        json_encode.invoke_closure(&mut [*self])?.string_to_utf8()
+    }
+
     ///
     /// Checks if two handles refer to the same object.
     ///
@@ -188,6 +241,18 @@ impl UnverifiedDartHandle {
         unsafe { ffi::Dart_IdentityEquals(a.handle, b.handle) }
     }
 
+    ///
+    /// Checks if two [`Type`](https://api.dart.dev/stable/2.7.1/dart-core/Type-class.html)s
+    /// are equal by invoking `==` on them.
+    ///
+    /// Unlike [`identity_eq`](UnverifiedDartHandle::identity_eq), this
+    /// will consider two separately constructed instantiations of the same
+    /// generic type (such as two `List<int>`s) to be equal.
+    ///
+    pub fn type_equals(a: Self, b: Self) -> Result<bool, Error> {
+        a.op_eq(b)?.get_bool()
+    }
+
     ///
     /// See [`Dart_HandleMessage`](::dart_sys::Dart_HandleMessage).
     ///
@@ -204,6 +269,21 @@ impl UnverifiedDartHandle {
         Self::new(handle).get_error()
     }
 
+    ///
+    /// See [`Dart_RunLoop`](::dart_sys::Dart_RunLoop).
+    ///
+    pub unsafe fn run_loop() -> Result<Self, Error> {
+        let handle = ffi::Dart_RunLoop();
+        Self::new(handle).get_error()
+    }
+
+    ///
+    /// See [`Dart_HasLivePorts`](::dart_sys::Dart_HasLivePorts).
+    ///
+    pub unsafe fn has_live_ports() -> bool {
+        ffi::Dart_HasLivePorts()
+    }
+
     ///
     /// Returns a handle to the [`Null`](https://api.dart.dev/stable/2.7.1/dart-core/Null-class.html) object.
     ///
@@ -247,6 +327,15 @@ impl UnverifiedDartHandle {
         }
     }
 
+    ///
+    /// Gets the `hashCode` of this object. Unlike hashing the handle
+    /// itself, this respects overridden `hashCode`s, so objects which
+    /// are [`equals`](UnverifiedDartHandle::equals) hash the same.
+    ///
+    pub fn dart_hash_code(&self) -> Result<i64, Error> {
+        self.get_field(Self::string_from_str("hashCode"))?.get_i64()
+    }
+
     ///
     /// Is `self` instanceof ty? `ty` must be a [`Type`](https://api.dart.dev/stable/2.7.1/dart-core/Type-class.html).
     ///
@@ -586,18 +675,45 @@ impl UnverifiedDartHandle {
     }
 
     pub fn string_to_utf8(&self) -> Result<String, Error> {
+        let string = String::from_utf8_lossy(self.string_utf8_bytes()?);
+        Ok(string.into_owned())
+    }
+
+    ///
+    /// Gets the UTF-8 encoding of a string without copying it into
+    /// Rust. The bytes are allocated by the VM in the current scope,
+    /// and as such are only valid for as long as this handle is.
+    ///
+    /// See [`Dart_StringToUTF8`](::dart_sys::Dart_StringToUTF8) for
+    /// more information.
+    ///
+    pub fn string_utf8_bytes(&self) -> Result<&[u8], Error> {
         unsafe {
             let mut ptr = MaybeUninit::<*mut u8>::uninit();
             let mut len = MaybeUninit::<isize>::uninit();
             let error_handle =
                 ffi::Dart_StringToUTF8(self.handle, ptr.as_mut_ptr(), len.as_mut_ptr());
             Self::new(error_handle).get_error()?;
-            let slice = std::slice::from_raw_parts_mut(ptr.assume_init(), len.assume_init() as _);
-            let string = String::from_utf8_lossy(slice);
-            Ok(string.into_owned())
+            let len = len.assume_init() as usize;
+            if len == 0 {
+                return Ok(&[]);
+            }
+            Ok(std::slice::from_raw_parts(ptr.assume_init(), len))
         }
     }
 
+    pub fn string_to_utf16(&self) -> Result<Vec<u16>, Error> {
+        let len = self.string_length()?;
+        let mut result = vec![0u16; len];
+        unsafe {
+            let mut len = len as isize;
+            let error_handle = ffi::Dart_StringToUTF16(self.handle, result.as_mut_ptr(), &mut len);
+            Self::new(error_handle).get_error()?;
+            result.truncate(len as usize);
+        }
+        Ok(result)
+    }
+
     pub fn string_storage_size(&self) -> Result<usize, Error> {
         unsafe {
             let mut result = MaybeUninit::<isize>::uninit();
@@ -683,6 +799,60 @@ impl UnverifiedDartHandle {
         }
     }
 
+    ///
+    /// See [`Dart_ListSetAsBytes`](::dart_sys::Dart_ListSetAsBytes).
+    ///
+    /// Each byte is written as one element of the list. This is a
+    /// single copy for typed data with one byte elements, and sets each
+    /// element individually otherwise.
+    ///
+    pub fn list_set_as_bytes(&self, offset: usize, bytes: &[u8]) -> Result<(), Error> {
+        unsafe {
+            Self::new(ffi::Dart_ListSetAsBytes(
+                self.handle,
+                offset as _,
+                bytes.as_ptr(),
+                bytes.len() as _,
+            ))
+            .get_error()
+            .map(|_| ())
+        }
+    }
+
+    ///
+    /// Gets the element type of a list as a `Type`, such as `String`
+    /// for a `List<String>`. Returns `None` for lists whose element type
+    /// is `dynamic`, as well as lists whose class isn't generic (such
+    /// as typed data).
+    ///
+    /// The embedding api doesn't expose type arguments, so this
+    /// reflects on the list using `dart:mirrors`, returning an error
+    /// should it be unavailable.
+    ///
+    pub fn list_element_type(&self) -> Result<Option<Self>, Error> {
+        if !self.is_list() {
+            return Err(Error::new_api("Expected a list.").unwrap());
+        }
+        let mirrors = Self::lookup_library(Self::string_from_str("dart:mirrors"))?;
+        let class = mirrors
+            .invoke(Self::string_from_str("reflect"), &mut [*self])?
+            .get_field(Self::string_from_str("type"))?;
+        let type_arguments = class.get_field(Self::string_from_str("typeArguments"))?;
+        if type_arguments.list_length()? != 1 {
+            return Ok(None);
+        }
+        let element = type_arguments.list_at(0)?;
+        let dynamic_type = mirrors
+            .invoke(Self::string_from_str("currentMirrorSystem"), &mut [])?
+            .get_field(Self::string_from_str("dynamicType"))?;
+        if element.equals(dynamic_type)? {
+            return Ok(None);
+        }
+        element
+            .get_field(Self::string_from_str("reflectedType"))
+            .map(Some)
+    }
+
     pub fn map_get_at(&self, key: Self) -> Result<Option<Self>, Error> {
         unsafe {
             let result = ffi::Dart_MapGetAt(self.handle, key.handle);
@@ -876,6 +1046,15 @@ impl UnverifiedDartHandle {
         unsafe { Self::new(ffi::Dart_GetClass(library.handle, name.handle)).get_error() }
     }
 
+    ///
+    /// Finds a loaded library given its url, such as `dart:core`.
+    ///
+    /// See [`Dart_LookupLibrary`](::dart_sys::Dart_LookupLibrary) for more information.
+    ///
+    pub fn lookup_library(url: Self) -> Result<Self, Error> {
+        unsafe { Self::new(ffi::Dart_LookupLibrary(url.handle)).get_error() }
+    }
+
     pub fn get_library_url_import(&self) -> Result<Self, Error> {
         unsafe { Self::new(ffi::Dart_LibraryUrl(self.handle)).get_error() }
     }
@@ -888,6 +1067,29 @@ impl UnverifiedDartHandle {
         unsafe { Self::new(ffi::Dart_GetLoadedLibraries()).get_error() }
     }
 
+    ///
+    /// Creates a persistent handle to this object, which keeps it alive
+    /// beyond the current scope until it is deleted.
+    ///
+    /// See [`Dart_NewPersistentHandle`](::dart_sys::Dart_NewPersistentHandle)
+    /// for more information.
+    ///
+    pub fn new_persistent(&self) -> ffi::Dart_PersistentHandle {
+        unsafe { ffi::Dart_NewPersistentHandle(self.handle) }
+    }
+
+    ///
+    /// Creates a handle in the current scope to the object referred to
+    /// by a persistent handle.
+    ///
+    /// # Safety
+    /// `handle` must be a persistent handle of the current isolate
+    /// which has not yet been deleted.
+    ///
+    pub unsafe fn from_persistent(handle: ffi::Dart_PersistentHandle) -> Self {
+        Self::new(ffi::Dart_HandleFromPersistent(handle))
+    }
+
     ///
     /// Invokes an operator for an instance. These methods are shorthand
     /// instead of directly using invoke. These methods will just use the
@@ -1001,9 +1203,37 @@ impl Deref for UnverifiedDartHandle {
     }
 }
 
+thread_local! {
+    static IN_DEBUG: std::cell::Cell<bool> = std::cell::Cell::new(false);
+}
+
+///
+/// Shows the object's `toString()`, or the error message should this
+/// be an error handle. Falls back to the raw pointer when there is no
+/// current isolate, when `toString()` throws, or when called again
+/// from within `toString()`.
+///
 impl Debug for UnverifiedDartHandle {
     fn fmt(&self, formatter: &mut Formatter) -> std::fmt::Result {
-        write!(formatter, "{:?}", self.handle)
+        let described = IN_DEBUG.with(|in_debug| {
+            if in_debug.get() || unsafe { ffi::Dart_CurrentIsolate().is_null() } {
+                return None;
+            }
+            in_debug.set(true);
+            let described = match self.get_error() {
+                Ok(x) => x.to_string().ok(),
+                Err(e) => Some(e.get_msg()),
+            };
+            in_debug.set(false);
+            described
+        });
+        match described {
+            Some(x) => formatter
+                .debug_tuple("UnverifiedDartHandle")
+                .field(&x.to_string_lossy())
+                .finish(),
+            None => write!(formatter, "{:?}", self.handle),
+        }
     }
 }
 
@@ -1129,6 +1359,23 @@ pub fn version_string() -> CString {
     }
 }
 
+///
+/// Throws `value` as a Dart exception, the same as `throw value` in
+/// Dart. This allows throwing any object, such as an instance of a
+/// custom exception class, rather than only error handles.
+///
+/// If there are no Dart frames on the stack, or `value` is itself an
+/// error, an error is returned. Otherwise this does not return, and
+/// the exception propagates to the Dart caller.
+///
+pub fn throw(value: impl DartHandle) -> Result<Infallible, Error> {
+    let handle =
+        unsafe { UnverifiedDartHandle::new(ffi::Dart_ThrowException(value.handle())).get_error() };
+
+    handle?;
+    panic!("Reached a non error handle after throwing an Exception!");
+}
+
 ///
 /// An error which may be any of [`ErrorKind`](crate::dart_handle::ErrorKind).
 ///
@@ -1138,6 +1385,12 @@ pub fn version_string() -> CString {
 pub struct Error {
     handle: UnverifiedDartHandle,
     kind: ErrorKind,
+    ///
+    /// The contained exception, wrapped as another error. This is only
+    /// created once asked for, and is always empty for the wrapped
+    /// exception itself so that the chain ends there.
+    ///
+    source: OnceCell<Option<Box<Error>>>,
 }
 
 unsafe impl DartHandle for Error {
@@ -1157,7 +1410,26 @@ unsafe impl DartHandle for Error {
 
 impl Error {
     pub(crate) unsafe fn of(handle: UnverifiedDartHandle, kind: ErrorKind) -> Self {
-        Self { handle, kind }
+        Self {
+            handle,
+            kind,
+            source: OnceCell::new(),
+        }
+    }
+
+    ///
+    /// Wraps the exception of an unhandled exception error as an error
+    /// of its own, which has no source.
+    ///
+    fn exception_source(&self) -> Option<Box<Self>> {
+        let exception = self.get_exception()?;
+        let mut source = Self::new_unhandled_exception(exception);
+        source.source = OnceCell::from(None);
+        Some(Box::new(source))
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
     }
 
     pub fn get_msg(&self) -> CString {
@@ -1196,32 +1468,93 @@ impl Error {
         }
     }
 
+    ///
+    /// Checks if this is an unhandled exception whose exception
+    /// object is an instance of `T`.
+    ///
+    pub fn is_exception_of<T: crate::dart_types::DartType>(&self) -> bool {
+        match self.get_exception() {
+            Some(exception) => T::THIS
+                .with(|ty| exception.instanceof(*ty))
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    ///
+    /// Checks if this is an unhandled exception whose exception
+    /// object is an instance of the class called `class` in the
+    /// library `library`, such as `FormatException` in `dart:core`.
+    ///
+    /// Returns `false` should the class not be found.
+    ///
+    pub fn matches_class(&self, library: &str, class: &str) -> bool {
+        let exception = match self.get_exception() {
+            Some(x) => x,
+            None => return false,
+        };
+        UnverifiedDartHandle::lookup_library(UnverifiedDartHandle::string_from_str(library))
+            .and_then(|library| {
+                UnverifiedDartHandle::get_class_of_library(
+                    library,
+                    UnverifiedDartHandle::string_from_str(class),
+                )
+            })
+            .and_then(|ty| exception.instanceof(ty))
+            .unwrap_or(false)
+    }
+
     pub fn new_api(message: &str) -> Result<Error, NulError> {
         let cstring = CString::new(message)?;
         unsafe {
             Ok(Self {
                 handle: UnverifiedDartHandle::new(ffi::Dart_NewApiError(cstring.as_ptr())),
                 kind: ErrorKind::Api,
+                source: OnceCell::new(),
             })
         }
     }
 
+    ///
+    /// Creates an api error with a message which may contain nul
+    /// bytes, replacing each of them with the escape sequence `\0`
+    /// instead of failing like [`new_api`](Error::new_api).
+    ///
+    pub fn new_api_sanitized(message: &str) -> Error {
+        if message.contains('\0') {
+            Self::new_api(&message.replace('\0', "\\0")).unwrap()
+        } else {
+            Self::new_api(message).unwrap()
+        }
+    }
+
+    ///
+    /// Creates an api error with a formatted message, such as
+    /// `Error::new_api_fmt(format_args!("Expected {} arguments", 2))`.
+    /// Nul bytes in the message are escaped as described in
+    /// [`new_api_sanitized`](Error::new_api_sanitized).
+    ///
+    pub fn new_api_fmt(args: std::fmt::Arguments) -> Error {
+        Self::new_api_sanitized(&std::fmt::format(args))
+    }
+
     pub fn new_compilation(message: &str) -> Result<Error, NulError> {
         let cstring = CString::new(message)?;
         unsafe {
             Ok(Self {
                 handle: UnverifiedDartHandle::new(ffi::Dart_NewCompilationError(cstring.as_ptr())),
                 kind: ErrorKind::Compilation,
+                source: OnceCell::new(),
             })
         }
     }
 
     pub fn new_unhandled_exception(exception: UnverifiedDartHandle) -> Error {
         unsafe {
-            Self {
-                handle: UnverifiedDartHandle::new(ffi::Dart_NewUnhandledExceptionError(*exception)),
-                kind: ErrorKind::UnhandledException,
-            }
+            Self::of(
+                UnverifiedDartHandle::new(ffi::Dart_NewUnhandledExceptionError(*exception)),
+                ErrorKind::UnhandledException,
+            )
         }
     }
 
@@ -1258,6 +1591,56 @@ impl Debug for Error {
     }
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, fmt: &mut Formatter) -> std::fmt::Result {
+        write!(fmt, "{}", self.get_msg().to_string_lossy())
+    }
+}
+
+///
+/// The [`source`](std::error::Error::source) of an unhandled exception
+/// is its exception, as returned by [`get_exception`](Error::get_exception),
+/// wrapped as another unhandled exception. That error has no source of
+/// its own, so the chain is at most two errors long.
+///
+/// The source is only created once asked for, and no Dart code is run
+/// to create it.
+///
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .get_or_init(|| self.exception_source())
+            .as_ref()
+            .map(|x| &**x as &(dyn std::error::Error + 'static))
+    }
+}
+
+///
+/// Turns a failed string conversion into an api error naming the
+/// position of the offending nul byte, so that `?` can be used on
+/// [`CString::new`] and friends in functions returning `Result<_, Error>`.
+///
+impl From<NulError> for Error {
+    fn from(error: NulError) -> Self {
+        Self::new_api_fmt(format_args!(
+            "String contains an interior nul byte at position {}",
+            error.nul_position()
+        ))
+    }
+}
+
+///
+/// Errors are equal when they are of the same [`ErrorKind`] and have
+/// the same message, as returned by [`get_msg`](Error::get_msg). The
+/// underlying handles are not compared, so two separately created
+/// errors with the same message compare equal.
+///
+impl PartialEq<Self> for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.get_msg() == other.get_msg()
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum ErrorKind {
     Api,
@@ -1284,6 +1667,22 @@ unsafe impl DartHandle for Result<UnverifiedDartHandle, Error> {
     }
 }
 
+impl From<Error> for Result<UnverifiedDartHandle, Error> {
+    fn from(error: Error) -> Self {
+        Err(error)
+    }
+}
+
+///
+/// A port owned by this side of the api, such as the native port an
+/// async function receives messages on, or the main port of the current
+/// isolate. To post messages, get a [`SendPort`] through
+/// [`sender`](Port::sender).
+///
+/// Ports are compared and hashed by their id, so they may be used as
+/// keys in maps.
+///
+#[derive(PartialEq, Eq, Hash, Debug)]
 pub struct Port {
     pub(crate) port: ffi::Dart_Port,
 }
@@ -1297,21 +1696,6 @@ impl Port {
         }
     }
 
-    pub fn post<T: DartHandle>(&self, handle: T) -> bool {
-        unsafe { ffi::Dart_Post(self.port, handle.handle()) }
-    }
-    pub fn post_cobject(&self, obj: crate::dart_cobject::CObject) -> bool {
-        unsafe {
-            self.post_raw_cobject(&mut obj.into_leak())
-        }
-    }
-    pub unsafe fn post_raw_cobject(&self, obj: &mut Dart_CObject) -> bool {
-        ffi::Dart_PostCObject(self.port, obj)
-    }
-    pub fn post_integer(&self, num: i64) -> bool {
-        unsafe { ffi::Dart_PostInteger(self.port, num) }
-    }
-
     pub unsafe fn new(port: ffi::Dart_Port) -> Result<(Self, UnverifiedDartHandle), Error> {
         let handle = ffi::Dart_NewSendPort(port);
         let handle = UnverifiedDartHandle::new(handle).get_error()?;
@@ -1322,6 +1706,47 @@ impl Port {
             port: unsafe { ffi::Dart_GetMainPortId() },
         }
     }
+    pub fn id(&self) -> ffi::Dart_Port {
+        self.port
+    }
+    ///
+    /// Creates a [`SendPort`] which posts messages to this port.
+    ///
+    pub fn sender(&self) -> SendPort {
+        SendPort { port: self.port }
+    }
+}
+
+///
+/// A port which messages can be posted to, such as a `SendPort` passed
+/// in from Dart. Unlike [`Port`], this makes no claim of owning the
+/// port.
+///
+/// A port which has been closed can not be detected ahead of time;
+/// instead the `post_*` functions return `false` should the message not
+/// be delivered.
+///
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SendPort {
+    port: ffi::Dart_Port,
+}
+
+impl SendPort {
+    ///
+    /// Creates a `SendPort` from a raw port id, returning `None`
+    /// should it be [`ILLEGAL_PORT`](ffi::ILLEGAL_PORT).
+    ///
+    pub unsafe fn from_port(port: ffi::Dart_Port) -> Option<Self> {
+        if port == ffi::ILLEGAL_PORT {
+            None
+        } else {
+            Some(Self { port })
+        }
+    }
+
+    ///
+    /// Gets the port of a Dart `SendPort` object.
+    ///
     pub fn from_send_port(handle: UnverifiedDartHandle) -> Result<Self, Error> {
         let mut port = MaybeUninit::uninit();
         let error_handle = unsafe { ffi::Dart_SendPortGetId(handle.handle, port.as_mut_ptr()) };
@@ -1332,6 +1757,76 @@ impl Port {
             })
         }
     }
+
+    pub fn id(&self) -> ffi::Dart_Port {
+        self.port
+    }
+
+    pub fn post<T: DartHandle>(&self, handle: T) -> bool {
+        unsafe { ffi::Dart_Post(self.port, handle.handle()) }
+    }
+    ///
+    /// Posts a `CObject` to this port. The message is copied by the VM,
+    /// so the memory allocated for it is freed once it has been posted.
+    ///
+    pub fn post_cobject(&self, obj: crate::dart_cobject::CObject) -> bool {
+        self.post_cobject_freeing(obj).0
+    }
+
+    ///
+    /// Posts a `CObject` to this port, also returning the number of
+    /// allocations freed after posting it. Useful to verify that
+    /// posting a message doesn't leak memory.
+    ///
+    /// This is only available with the `debug-hooks` feature.
+    ///
+    #[cfg(feature = "debug-hooks")]
+    pub fn post_cobject_counted(&self, obj: crate::dart_cobject::CObject) -> (bool, usize) {
+        self.post_cobject_freeing(obj)
+    }
+
+    fn post_cobject_freeing(&self, obj: crate::dart_cobject::CObject) -> (bool, usize) {
+        unsafe {
+            let mut obj = obj.into_leak();
+            let posted = self.post_raw_cobject(&mut obj);
+            (posted, crate::dart_cobject::CObject::free_leaked(obj))
+        }
+    }
+    ///
+    /// Posts a `CObject` to this port without taking ownership of it,
+    /// so that it may be posted again.
+    ///
+    /// Typed data with a finalizer is handed over to the VM once it
+    /// has been posted, so it must not be posted by reference more
+    /// than once.
+    ///
+    pub fn post_ref(&self, obj: &crate::dart_cobject::CObject) -> bool {
+        let mut lock = obj.as_non_leak();
+        unsafe { self.post_raw_cobject(&mut lock.object) }
+    }
+
+    ///
+    /// Posts the same `CObject` to each of `ports`, building its raw
+    /// representation only once. Returns whether posting succeeded
+    /// for each port, in order.
+    ///
+    /// See [`post_ref`](SendPort::post_ref) for restrictions on typed
+    /// data.
+    ///
+    pub fn post_to_all(ports: &[SendPort], obj: &crate::dart_cobject::CObject) -> Vec<bool> {
+        let mut lock = obj.as_non_leak();
+        ports
+            .iter()
+            .map(|port| unsafe { port.post_raw_cobject(&mut lock.object) })
+            .collect()
+    }
+
+    pub unsafe fn post_raw_cobject(&self, obj: &mut Dart_CObject) -> bool {
+        ffi::Dart_PostCObject(self.port, obj)
+    }
+    pub fn post_integer(&self, num: i64) -> bool {
+        unsafe { ffi::Dart_PostInteger(self.port, num) }
+    }
 }
 
 pub struct NativePort {
@@ -1364,6 +1859,152 @@ impl NativePort {
     }
 }
 
+///
+/// A native port paired with a `SendPort` which may be handed to
+/// Dart so that it can send messages to the port's handler.
+///
+pub struct ReceivePort {
+    native: NativePort,
+    send_port: UnverifiedDartHandle,
+}
+
+impl ReceivePort {
+    ///
+    /// Creates a native port called `name` which will call `handler`
+    /// with each message it receives, along with its `SendPort`.
+    ///
+    /// # Safety
+    /// The same as [`NativePort::new_native`], since `handler` may be
+    /// called concurrently on any thread.
+    ///
+    pub unsafe fn new(
+        name: CString,
+        handler: unsafe extern "C" fn(
+            dest_port_id: ffi::Dart_Port,
+            message: *mut ffi::Dart_CObject,
+        ),
+    ) -> Result<Self, Error> {
+        let native = NativePort::new_native(name, handler)
+            .ok_or_else(|| Error::new_api("Could not create a native port.").unwrap())?;
+        let (_, send_port) = Port::new(native.port())?;
+        Ok(Self { native, send_port })
+    }
+
+    ///
+    /// The `SendPort` instance which sends to this port. Return this
+    /// to Dart to set up a reply channel.
+    ///
+    pub fn send_port(&self) -> UnverifiedDartHandle {
+        self.send_port
+    }
+
+    pub fn port(&self) -> ffi::Dart_Port {
+        self.native.port()
+    }
+
+    pub fn close(self) -> bool {
+        self.native.close()
+    }
+}
+
+///
+/// A handle which refers to an object without keeping it alive,
+/// and which lives beyond the current scope.
+///
+/// See [`Dart_NewWeakPersistentHandle`](::dart_sys::Dart_NewWeakPersistentHandle)
+/// for more information.
+///
+pub struct WeakPersistentHandle {
+    handle: ffi::Dart_WeakPersistentHandle,
+    isolate: ffi::Dart_Isolate,
+    alive: Arc<AtomicBool>,
+}
+
+impl WeakPersistentHandle {
+    ///
+    /// Creates a weak persistent handle to `object`, returning `None`
+    /// should the VM refuse to create one (for example, if `object` is
+    /// not allocated on the heap).
+    ///
+    pub fn new(object: UnverifiedDartHandle) -> Option<Self> {
+        unsafe extern "C" fn finalize(
+            _isolate_callback_data: *mut c_void,
+            _handle: ffi::Dart_WeakPersistentHandle,
+            peer: *mut c_void,
+        ) {
+            let alive = Arc::from_raw(peer as *const AtomicBool);
+            alive.store(false, Ordering::SeqCst);
+        }
+
+        let alive = Arc::new(AtomicBool::new(true));
+        let peer = Arc::into_raw(alive.clone()) as *mut c_void;
+        let handle =
+            unsafe { ffi::Dart_NewWeakPersistentHandle(object.handle, peer, 0, Some(finalize)) };
+        if handle.is_null() {
+            // SAFETY:
+            // The VM has not taken the peer, so we still own it.
+            unsafe { drop(Arc::from_raw(peer as *const AtomicBool)) };
+            None
+        } else {
+            let isolate = unsafe { ffi::Dart_CurrentIsolate() };
+            Some(Self {
+                handle,
+                isolate,
+                alive,
+            })
+        }
+    }
+
+    ///
+    /// Checks if the referenced object has yet to be collected.
+    ///
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst)
+    }
+
+    ///
+    /// Creates a handle to the referenced object in the current scope,
+    /// returning `None` should it have already been collected.
+    ///
+    /// See [`Dart_HandleFromWeakPersistent`](::dart_sys::Dart_HandleFromWeakPersistent)
+    /// for more information.
+    ///
+    pub fn upgrade(&self) -> Option<UnverifiedDartHandle> {
+        if !self.is_alive() {
+            return None;
+        }
+        let handle = unsafe { ffi::Dart_HandleFromWeakPersistent(self.handle) };
+        if handle.is_null() {
+            return None;
+        }
+        let handle = unsafe { UnverifiedDartHandle::new(handle) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(handle)
+        }
+    }
+}
+
+///
+/// Deletes the handle from the isolate it was created in, which needn't
+/// be the current isolate. Handles whose object was collected, or whose
+/// isolate has shut down, were already freed by the VM.
+///
+impl Drop for WeakPersistentHandle {
+    fn drop(&mut self) {
+        if self.is_alive() {
+            unsafe {
+                ffi::Dart_DeleteWeakPersistentHandle(self.isolate, self.handle);
+                // SAFETY:
+                // Deleting the handle means that the finalizer will never
+                // run, so the peer's reference is ours to release.
+                drop(Arc::from_raw(Arc::as_ptr(&self.alive)));
+            }
+        }
+    }
+}
+
 pub trait TypedData: 'static + Copy + Clone + Debug {
     const TYPE: ffi::Dart_TypedData_Type;
 }
@@ -1383,10 +2024,331 @@ impl_typed_data!(
     f32, Float32, f64, Float64
 );
 
+///
+/// Gets the name of the Dart class for a typed data type, such as
+/// `Uint8List` for [`Uint8`](ffi::Dart_TypedData_Type::Uint8).
+///
+pub fn typed_data_type_name(ty: ffi::Dart_TypedData_Type) -> &'static str {
+    use ffi::Dart_TypedData_Type::*;
+    match ty {
+        ByteData => "ByteData",
+        Int8 => "Int8List",
+        Uint8 => "Uint8List",
+        Uint8Clamped => "Uint8ClampedList",
+        Int16 => "Int16List",
+        Uint16 => "Uint16List",
+        Int32 => "Int32List",
+        Uint32 => "Uint32List",
+        Int64 => "Int64List",
+        Uint64 => "Uint64List",
+        Float32 => "Float32List",
+        Float64 => "Float64List",
+        Float32x4 => "Float32x4List",
+        Invalid => "<Invalid Typed Data>",
+    }
+}
+
+///
+/// Direct access to the backing store of a typed data object,
+/// acquired through [`Dart_TypedDataAcquireData`](ffi::Dart_TypedDataAcquireData)
+/// and released once this guard is dropped.
+///
+/// While the guard is alive the VM may not move or collect the
+/// object, and no other Dart api calls may be made on this thread
+/// besides releasing the data. Keep these guards short lived.
+///
+pub struct TypedDataGuard<'a, T: TypedData> {
+    handle: UnverifiedDartHandle,
+    data: *mut T,
+    len: usize,
+    _phantom: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T: TypedData> TypedDataGuard<'a, T> {
+    ///
+    /// Acquires the data of `handle` if it is a typed data object
+    /// whose element type is `T`. Returns `Ok(None)` if it is typed
+    /// data of another type.
+    ///
+    /// The guard borrows `handle`, so that it can't outlive the
+    /// handle it was acquired through.
+    ///
+    pub fn acquire(handle: &'a UnverifiedDartHandle) -> Result<Option<Self>, Error> {
+        let mut ty = MaybeUninit::<ffi::Dart_TypedData_Type>::uninit();
+        let mut data = std::ptr::null_mut();
+        let mut len = 0;
+        unsafe {
+            UnverifiedDartHandle::new(ffi::Dart_TypedDataAcquireData(
+                handle.handle,
+                ty.as_mut_ptr(),
+                &mut data,
+                &mut len,
+            ))
+            .get_error()?;
+            if ty.assume_init() != T::TYPE {
+                UnverifiedDartHandle::new(ffi::Dart_TypedDataReleaseData(handle.handle))
+                    .get_error()?;
+                return Ok(None);
+            }
+        }
+        Ok(Some(Self {
+            handle: *handle,
+            data: data as *mut T,
+            len: len as usize,
+            _phantom: PhantomData,
+        }))
+    }
+}
+
+impl<'a, T: TypedData> Deref for TypedDataGuard<'a, T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        if self.len == 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+}
+
+impl<'a, T: TypedData> DerefMut for TypedDataGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        if self.len == 0 {
+            return &mut [];
+        }
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl<'a, T: TypedData> Drop for TypedDataGuard<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::Dart_TypedDataReleaseData(self.handle.handle);
+        }
+    }
+}
+
+///
+/// Handles messages for the current isolate until `timeout` has
+/// elapsed, by repeatedly calling [`Dart_WaitForEvent`](ffi::Dart_WaitForEvent).
+///
+/// This is meant for extensions which drive the message loop of an
+/// isolate they own, such as a helper isolate. It requires there to
+/// be a current isolate and that no Dart code is running on it, so it
+/// must not be called from a native function invoked by Dart.
+///
+/// Returns early with the error should handling a message fail.
+///
+pub fn pump_events(timeout: Duration) -> Result<(), Error> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(());
+        }
+        let remaining = (deadline - now).as_millis().max(1);
+        unsafe {
+            UnverifiedDartHandle::wait_for_event(remaining.try_into().unwrap_or(i64::MAX))?;
+        }
+    }
+}
+
+///
+/// Handles messages for the current isolate until `done` returns
+/// `true`, or until the isolate has no open ports left, after which no
+/// more messages can arrive. `done` is checked before each wait of up
+/// to `poll` for a message, so it is called at least once every `poll`.
+///
+/// Each message is handled in its own scope, so that handles created
+/// while handling messages don't pile up.
+///
+/// An open port, such as a [`ReceivePort`] which is never closed, keeps
+/// the isolate from running out of ports, so `done` is what ends the
+/// loop in most cases.
+///
+/// This is meant for embedders which don't run the message loop of
+/// an isolate themselves, and is subject to the same requirements as
+/// [`pump_events`]: there must be a current isolate with no Dart code
+/// running on it. In particular it must not be called from a native
+/// function, nor on a thread which is already running the isolate's
+/// message loop, such as the one the VM started the isolate on.
+///
+/// Returns early with the error should handling a message fail.
+///
+pub fn drive_events(poll: Duration, mut done: impl FnMut() -> bool) -> Result<(), Error> {
+    let poll = poll.as_millis().max(1).try_into().unwrap_or(i64::MAX);
+    unsafe {
+        while !done() && UnverifiedDartHandle::has_live_ports() {
+            let _scope = DartScope::enter();
+            UnverifiedDartHandle::wait_for_event(poll)?;
+            UnverifiedDartHandle::handle_message()?;
+        }
+    }
+    Ok(())
+}
+
+///
+/// A cache of objects kept alive by persistent handles, which is meant
+/// to be stored in a thread local.
+///
+/// Objects are cached per isolate, keyed on its main port rather than
+/// its address, since the address of an isolate which has shut down
+/// may be reused by one created later on. The persistent handles of an
+/// isolate which has shut down are never looked up again, and are
+/// freed by the VM along with the isolate.
+///
+pub(crate) struct IsolateCache<K> {
+    handles: RefCell<HashMap<(ffi::Dart_Port, K), ffi::Dart_PersistentHandle>>,
+}
+
+impl<K: Eq + Hash> IsolateCache<K> {
+    pub(crate) fn new() -> Self {
+        Self {
+            handles: RefCell::new(HashMap::new()),
+        }
+    }
+
+    ///
+    /// Gets the object cached under `key` for the current isolate,
+    /// creating and caching it using `create` should there be none.
+    ///
+    /// # Panics
+    /// Panics should there be no current isolate.
+    ///
+    pub(crate) fn get_or_try_insert_with(
+        &self,
+        key: K,
+        create: impl FnOnce() -> Result<UnverifiedDartHandle, Error>,
+    ) -> Result<UnverifiedDartHandle, Error> {
+        let port = unsafe {
+            assert!(
+                !ffi::Dart_CurrentIsolate().is_null(),
+                "There is no current isolate"
+            );
+            ffi::Dart_GetMainPortId()
+        };
+        let key = (port, key);
+        if let Some(handle) = self.handles.borrow().get(&key) {
+            // SAFETY: The handle was created in the current isolate,
+            // and is never deleted.
+            return Ok(unsafe { UnverifiedDartHandle::from_persistent(*handle) });
+        }
+        // The cache isn't borrowed while `create` runs, so that it may
+        // use the cache itself.
+        let handle = create()?;
+        self.handles
+            .borrow_mut()
+            .insert(key, handle.new_persistent());
+        Ok(handle)
+    }
+}
+
 pub unsafe fn set_thread_name(name: &CStr) {
     ffi::Dart_SetThreadName(name.as_ptr());
 }
 
+///
+/// A guard which enters a new scope upon creation and exits
+/// it upon being dropped, keeping calls to [`enter_scope`] and
+/// [`exit_scope`] balanced.
+///
+pub struct DartScope {
+    // Scopes belong to the thread they were entered on.
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl DartScope {
+    ///
+    /// Enters a new scope, which is exited when the returned
+    /// guard is dropped.
+    ///
+    /// # Safety
+    /// Any handles created while the guard is alive are invalidated
+    /// when it is dropped, and must not be used after that point.
+    ///
+    pub unsafe fn enter() -> Self {
+        enter_scope();
+        #[cfg(feature = "debug-hooks")]
+        SCOPE_DEPTH.with(|x| x.set(x.get() + 1));
+        Self {
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for DartScope {
+    fn drop(&mut self) {
+        unsafe { exit_scope() }
+        #[cfg(feature = "debug-hooks")]
+        SCOPE_DEPTH.with(|x| x.set(x.get() - 1));
+    }
+}
+
+#[cfg(feature = "debug-hooks")]
+thread_local! {
+    static SCOPE_DEPTH: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+///
+/// Gets the number of [`DartScope`]s currently entered on this
+/// thread. Useful to verify that scopes are kept balanced.
+///
+/// Scopes entered by the VM or through [`enter_scope`] directly are
+/// not counted. This is only available with the `debug-hooks`
+/// feature.
+///
+#[cfg(feature = "debug-hooks")]
+pub fn scope_depth() -> usize {
+    SCOPE_DEPTH.with(|x| x.get())
+}
+
+///
+/// A guard which enters an isolate upon creation and exits it upon
+/// being dropped, for extensions which drive isolates they created
+/// themselves, such as a helper isolate.
+///
+/// Should another isolate be current on this thread when entering,
+/// it is exited and then entered again once the guard is dropped.
+///
+pub struct IsolateScope {
+    previous: ffi::Dart_Isolate,
+}
+
+impl IsolateScope {
+    ///
+    /// Enters `isolate` on this thread until the returned guard is
+    /// dropped.
+    ///
+    /// # Safety
+    /// - `isolate` must be a valid isolate which has not been shut
+    /// down, and must not be current on any other thread.
+    /// - Should an isolate already be current on this thread, it must
+    /// have no Dart frames on the stack. This means this must not be
+    /// called from within a native function invoked by Dart.
+    /// - Guards must be dropped in the reverse order of their
+    /// creation, and handles created within `isolate` must not be
+    /// used after the guard is dropped.
+    ///
+    pub unsafe fn enter(isolate: ffi::Dart_Isolate) -> Self {
+        let previous = ffi::Dart_CurrentIsolate();
+        if !previous.is_null() {
+            ffi::Dart_ExitIsolate();
+        }
+        ffi::Dart_EnterIsolate(isolate);
+        Self { previous }
+    }
+}
+
+impl Drop for IsolateScope {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::Dart_ExitIsolate();
+            if !self.previous.is_null() {
+                ffi::Dart_EnterIsolate(self.previous);
+            }
+        }
+    }
+}
+
 pub unsafe fn enter_scope() {
     ffi::Dart_EnterScope();
 }
@@ -1394,3 +2356,111 @@ pub unsafe fn enter_scope() {
 pub unsafe fn exit_scope() {
     ffi::Dart_ExitScope();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        typed_data_type_name, DartScope, Error, IsolateScope, Port, SendPort, TypedDataGuard,
+        UnverifiedDartHandle, WeakPersistentHandle,
+    };
+    use crate::dart_native_arguments::NativeArguments;
+    use crate::dart_types::{
+        boolean::Boolean,
+        class::DartClass,
+        closure::Closure,
+        d_string::DString,
+        double::{Double, OrdDouble},
+        dynamic::Dynamic,
+        future::Future,
+        integer::Integer,
+        list::List,
+        map::Map,
+        set::Set,
+    };
+    use dart_sys as ffi;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::collections::HashSet;
+
+    // Handles are only valid on the thread they were created on.
+    assert_not_impl_any!(UnverifiedDartHandle: Send, Sync);
+    assert_not_impl_any!(Error: Send, Sync);
+    assert_not_impl_any!(WeakPersistentHandle: Send, Sync);
+    assert_not_impl_any!(NativeArguments: Send, Sync);
+    assert_not_impl_any!(DartScope: Send, Sync);
+    assert_not_impl_any!(IsolateScope: Send, Sync);
+    assert_not_impl_any!(TypedDataGuard<'static, u8>: Send, Sync);
+    assert_not_impl_any!(Boolean: Send, Sync);
+    assert_not_impl_any!(DartClass: Send, Sync);
+    assert_not_impl_any!(Closure: Send, Sync);
+    assert_not_impl_any!(DString: Send, Sync);
+    assert_not_impl_any!(Double: Send, Sync);
+    assert_not_impl_any!(OrdDouble: Send, Sync);
+    assert_not_impl_any!(Dynamic: Send, Sync);
+    assert_not_impl_any!(Future: Send, Sync);
+    assert_not_impl_any!(Integer: Send, Sync);
+    assert_not_impl_any!(List<Integer>: Send, Sync);
+    assert_not_impl_any!(Map<DString, Integer>: Send, Sync);
+    assert_not_impl_any!(Set<Integer>: Send, Sync);
+    // Ports are plain ids, and may be shared.
+    assert_impl_all!(Port: Send, Sync);
+    assert_impl_all!(SendPort: Send, Sync);
+
+    fn port(id: ffi::Dart_Port) -> Port {
+        unsafe { Port::from_port(id) }.unwrap()
+    }
+
+    #[test]
+    fn ports_are_keyed_by_id() {
+        let mut ports = HashSet::new();
+        assert!(ports.insert(port(1)));
+        assert!(ports.insert(port(2)));
+        assert!(!ports.insert(port(1)));
+        assert_eq!(ports.len(), 2);
+        assert!(ports.contains(&port(2)));
+        assert!(!ports.contains(&port(3)));
+        assert_eq!(format!("{:?}", port(5)), "Port { port: 5 }");
+    }
+
+    #[test]
+    fn senders_compare_by_port() {
+        let senders = [1, 2, 1]
+            .iter()
+            .map(|&id| port(id).sender())
+            .collect::<HashSet<SendPort>>();
+        assert_eq!(senders.len(), 2);
+        assert!(unsafe { Port::from_port(ffi::ILLEGAL_PORT) }.is_none());
+    }
+
+    #[test]
+    fn typed_data_types_have_distinct_names() {
+        use ffi::Dart_TypedData_Type::*;
+        let types = [
+            ByteData,
+            Int8,
+            Uint8,
+            Uint8Clamped,
+            Int16,
+            Uint16,
+            Int32,
+            Uint32,
+            Int64,
+            Uint64,
+            Float32,
+            Float64,
+            Float32x4,
+            Invalid,
+        ];
+        let names = types
+            .iter()
+            .map(|&ty| typed_data_type_name(ty))
+            .collect::<HashSet<_>>();
+        assert_eq!(names.len(), types.len());
+        for &ty in &types[1..types.len() - 1] {
+            assert!(typed_data_type_name(ty).ends_with("List"));
+        }
+        assert_eq!(typed_data_type_name(ByteData), "ByteData");
+        assert_eq!(typed_data_type_name(Uint8Clamped), "Uint8ClampedList");
+        assert_eq!(typed_data_type_name(Float64), "Float64List");
+        assert_eq!(typed_data_type_name(Invalid), "<Invalid Typed Data>");
+    }
+}
@@ -1,7 +1,12 @@
 use crate::dart_handle::Error;
-use crate::dart_handle::{DartHandle, UnverifiedDartHandle};
+use crate::dart_handle::{
+    typed_data_type_name, DartHandle, TypedData, TypedDataGuard, UnverifiedDartHandle,
+};
 use crate::dart_types::d_string::DString;
+use crate::dart_types::list::List;
+use crate::dart_types::DartType;
 use dart_sys as ffi;
+use std::convert::TryInto;
 use std::ffi::CStr;
 use std::mem::MaybeUninit;
 
@@ -153,6 +158,114 @@ impl NativeArguments {
         }
     }
 
+    ///
+    /// Attempts to get a number from the argument list as an `f64`,
+    /// accepting both doubles and integers as a Dart `num` would.
+    /// Integers too large to be represented exactly are rounded.
+    ///
+    /// Returns an error should the argument be neither.
+    ///
+    pub fn get_num_arg(&self, idx: usize) -> Result<f64, Error> {
+        let handle = self.get_native_argument(idx).get_error()?;
+        if handle.is_integer() {
+            handle.get_i64().map(|x| x as f64)
+        } else {
+            self.get_f64_arg(idx)
+        }
+    }
+
+    ///
+    /// Checks if the argument at `idx` was either not passed, or is
+    /// `null`, as is the case for omitted optional parameters.
+    ///
+    fn is_absent(&self, idx: usize) -> Result<bool, Error> {
+        if idx >= self.get_native_argument_count() {
+            return Ok(true);
+        }
+        Ok(self.get_native_argument(idx).get_error()?.is_null())
+    }
+
+    ///
+    /// Attempts to retrieve an optional string from the argument list,
+    /// returning `None` should it be absent or `null`, and an error
+    /// should it be anything but a string.
+    ///
+    pub fn get_string_arg_opt(&self, idx: usize) -> Result<Option<String>, Error> {
+        if self.is_absent(idx)? {
+            Ok(None)
+        } else {
+            self.get_string_arg(idx).map(Some)
+        }
+    }
+
+    ///
+    /// Attempts to retrieve an optional boolean from the argument list.
+    /// See [`get_string_arg_opt`](NativeArguments::get_string_arg_opt).
+    ///
+    pub fn get_bool_arg_opt(&self, idx: usize) -> Result<Option<bool>, Error> {
+        if self.is_absent(idx)? {
+            Ok(None)
+        } else {
+            self.get_bool_arg(idx).map(Some)
+        }
+    }
+
+    ///
+    /// Attempts to retrieve an optional integer from the argument list.
+    /// See [`get_string_arg_opt`](NativeArguments::get_string_arg_opt).
+    ///
+    pub fn get_i64_arg_opt(&self, idx: usize) -> Result<Option<i64>, Error> {
+        if self.is_absent(idx)? {
+            Ok(None)
+        } else {
+            self.get_i64_arg(idx).map(Some)
+        }
+    }
+
+    ///
+    /// Attempts to retrieve an optional double from the argument list.
+    /// See [`get_string_arg_opt`](NativeArguments::get_string_arg_opt).
+    ///
+    pub fn get_f64_arg_opt(&self, idx: usize) -> Result<Option<f64>, Error> {
+        if self.is_absent(idx)? {
+            Ok(None)
+        } else {
+            self.get_f64_arg(idx).map(Some)
+        }
+    }
+
+    ///
+    /// Attempts to retrieve a list from the argument list, returning
+    /// an error should it not be a list.
+    ///
+    /// The elements of the list are not checked to be of type `T`,
+    /// see [`List::from_handle_checked`] for that.
+    ///
+    pub fn get_list_arg<T: DartType>(&self, idx: usize) -> Result<List<T>, Error> {
+        let handle = self.get_native_argument(idx).get_error()?;
+        List::from_handle(handle)
+            .map_err(|_| Error::new_api(&format!("Argument {} is not a List.", idx)).unwrap())
+    }
+
+    ///
+    /// Attempts to retrieve a typed data list (such as a `Uint8List`)
+    /// from the argument list, returning an error should it not be
+    /// typed data of the element type `T`.
+    ///
+    pub fn get_typed_list_arg<T: TypedData>(&self, idx: usize) -> Result<List<T>, Error> {
+        let handle = self.get_native_argument(idx).get_error()?;
+        if handle.is_typed_data() && handle.typed_data_get_type() == T::TYPE {
+            Ok(List::from_handle(handle).ok().unwrap())
+        } else {
+            Err(Error::new_api(&format!(
+                "Argument {} is not a {}.",
+                idx,
+                typed_data_type_name(T::TYPE)
+            ))
+            .unwrap())
+        }
+    }
+
     ///
     /// Sets an instance as the return value. This (and associated
     /// `set_*_return` functions) will be what is received on the
@@ -191,6 +304,21 @@ impl NativeArguments {
             ffi::Dart_SetDoubleReturnValue(self.args, val);
         }
     }
+
+    ///
+    /// Sets a typed data return value (such as a `Uint8List`) holding
+    /// a copy of `data`. The VM owns the new typed data, so `data` need
+    /// not outlive this call. See [`set_return`](NativeArguments::set_return)
+    /// for more information.
+    ///
+    pub fn set_typed_data_return_copied<T: TypedData>(&self, data: &[T]) -> Result<(), Error> {
+        let handle = UnverifiedDartHandle::new_typed_data(T::TYPE, data.len())?;
+        if let Some(mut guard) = TypedDataGuard::<T>::acquire(&handle)? {
+            guard.copy_from_slice(data);
+        }
+        self.set_return(handle);
+        Ok(())
+    }
 }
 
 ///
@@ -267,4 +395,46 @@ impl NativeArgumentValue {
         }
         Ok(result)
     }
+
+    ///
+    /// Gets the value as an `i64` if this is any of the integer
+    /// variants. Returns `None` for a `UInt64` which does not fit.
+    ///
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            NativeArgumentValue::Int32(x) => Some(x as i64),
+            NativeArgumentValue::UInt32(x) => Some(x as i64),
+            NativeArgumentValue::Int64(x) => Some(x),
+            NativeArgumentValue::UInt64(x) => x.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            NativeArgumentValue::Double(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            NativeArgumentValue::Bool(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_string(&self) -> Option<&DString> {
+        match self {
+            NativeArgumentValue::String(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_instance(&self) -> Option<&UnverifiedDartHandle> {
+        match self {
+            NativeArgumentValue::Instance(x) => Some(x),
+            _ => None,
+        }
+    }
 }
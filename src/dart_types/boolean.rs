@@ -30,6 +30,18 @@ impl Boolean {
             value
         }
     }
+
+    ///
+    /// Returns `if_true` should this be `true`, otherwise `if_false`.
+    ///
+    #[inline]
+    pub fn select<T>(&self, if_true: T, if_false: T) -> T {
+        if self.value() {
+            if_true
+        } else {
+            if_false
+        }
+    }
 }
 
 impl std::ops::Not for Boolean {
@@ -39,6 +51,12 @@ impl std::ops::Not for Boolean {
     }
 }
 
+impl Default for Boolean {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
 impl From<bool> for Boolean {
     fn from(x: bool) -> Self {
         Self::new(x)
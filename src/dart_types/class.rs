@@ -0,0 +1,70 @@
+use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
+use crate::dart_types::closure::Closure;
+use std::ops::Deref;
+
+///
+/// A Dart class (or more precisely, its `Type`), which may be
+/// used to access static members of the class.
+///
+#[derive(Copy, Clone)]
+pub struct DartClass {
+    handle: UnverifiedDartHandle,
+}
+
+impl DartClass {
+    ///
+    /// Finds the class called `class_name` in the library imported
+    /// as `library_url`, such as `package:my_package/my_package.dart`.
+    ///
+    pub fn new(library_url: &str, class_name: &str) -> Result<Self, Error> {
+        let library = UnverifiedDartHandle::lookup_library(UnverifiedDartHandle::string_from_str(
+            library_url,
+        ))?;
+        let handle = UnverifiedDartHandle::get_class_of_library(
+            library,
+            UnverifiedDartHandle::string_from_str(class_name),
+        )?;
+        Ok(Self { handle })
+    }
+
+    pub fn library(&self) -> Result<UnverifiedDartHandle, Error> {
+        self.handle.library_from_class()
+    }
+
+    ///
+    /// Tears off the static method called `name`, returning a
+    /// [`Closure`] which may be called repeatedly.
+    ///
+    pub fn static_method(&self, name: &str) -> Result<Closure, Error> {
+        let closure = UnverifiedDartHandle::get_static_method_closure(
+            self.library()?,
+            self.handle,
+            UnverifiedDartHandle::string_from_str(name),
+        )?;
+        Closure::from_handle(closure)
+            .map_err(|_| Error::new_api(&format!("`{}` is not a static method.", name)).unwrap())
+    }
+}
+
+impl Deref for DartClass {
+    type Target = UnverifiedDartHandle;
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+unsafe impl DartHandle for DartClass {
+    fn handle(&self) -> dart_sys::Dart_Handle {
+        self.handle.handle()
+    }
+    fn safe_handle(&self) -> UnverifiedDartHandle {
+        self.handle
+    }
+    fn from_handle(handle: UnverifiedDartHandle) -> Result<Self, UnverifiedDartHandle> {
+        if handle.is_type() {
+            Ok(Self { handle })
+        } else {
+            Err(handle)
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
+use crate::dart_types::dynamic::Dynamic;
+use std::ops::Deref;
+
+///
+/// A Dart closure, such as a lambda or a torn off method, which
+/// may be called from Rust.
+///
+#[derive(Copy, Clone)]
+pub struct Closure {
+    handle: UnverifiedDartHandle,
+}
+
+impl Closure {
+    pub fn call(&self, args: &mut [UnverifiedDartHandle]) -> Result<Dynamic, Error> {
+        self.handle.invoke_closure(args).map(Dynamic::from)
+    }
+
+    ///
+    /// Gets the function which this closure calls.
+    ///
+    pub fn function(&self) -> Result<UnverifiedDartHandle, Error> {
+        self.handle.function_from_closure()
+    }
+}
+
+impl Deref for Closure {
+    type Target = UnverifiedDartHandle;
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+unsafe impl DartHandle for Closure {
+    fn handle(&self) -> dart_sys::Dart_Handle {
+        self.handle.handle()
+    }
+    fn safe_handle(&self) -> UnverifiedDartHandle {
+        self.handle
+    }
+    fn from_handle(handle: UnverifiedDartHandle) -> Result<Self, UnverifiedDartHandle> {
+        if handle.is_closure() {
+            Ok(Self { handle })
+        } else {
+            Err(handle)
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use super::integer::Integer;
-use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
+use crate::dart_handle::{DartHandle, Error, IsolateCache, UnverifiedDartHandle};
 use crate::dart_types::boolean::Boolean;
+use crate::dart_types::class::DartClass;
+use crate::dart_types::double::Double;
 use crate::dart_types::list::{List, ListLike};
 use crate::dart_types::DartType;
 use crate::dart_unwrap;
@@ -32,10 +34,73 @@ impl DString {
         })
     }
 
+    pub fn from_utf32(values: &[char]) -> Result<Self, Error> {
+        let values = values.iter().map(|&x| x as i32).collect::<Vec<_>>();
+        Ok(Self {
+            handle: UnverifiedDartHandle::string_from_utf32(&values)?,
+        })
+    }
+
+    pub fn from_chars(chars: impl Iterator<Item = char>) -> Result<Self, Error> {
+        let values = chars.map(|x| x as i32).collect::<Vec<_>>();
+        Ok(Self {
+            handle: UnverifiedDartHandle::string_from_utf32(&values)?,
+        })
+    }
+
+    ///
+    /// Creates a string from formatting arguments, as created by
+    /// [`format_args!`]. See also [`d_format!`](crate::d_format).
+    ///
+    pub fn from_fmt(args: std::fmt::Arguments) -> Self {
+        Self::new(&std::fmt::format(args))
+    }
+
+    ///
+    /// Gets a string with the contents of `string`, reusing the same
+    /// Dart string for each call with equal contents instead of
+    /// allocating a new one. Useful for constants which are returned
+    /// frequently.
+    ///
+    /// The strings are kept alive by persistent handles, cached per
+    /// thread and isolate, and are only freed once their isolate shuts
+    /// down.
+    ///
+    /// # Panics
+    /// Panics should there be no current isolate.
+    ///
+    pub fn interned(string: &'static str) -> Self {
+        thread_local! {
+            static INTERNED: IsolateCache<&'static str> = IsolateCache::new();
+        }
+        let handle = INTERNED.with(|x| {
+            x.get_or_try_insert_with(string, || {
+                Ok(UnverifiedDartHandle::string_from_str(string))
+            })
+        });
+        Self {
+            handle: dart_unwrap!(handle),
+        }
+    }
+
+    pub fn from_char(c: char) -> Self {
+        Self::new(c.encode_utf8(&mut [0; 4]))
+    }
+
     pub fn as_bytes(&self) -> Vec<u8> {
         self.as_string().into_bytes()
     }
 
+    ///
+    /// Iterates over the UTF-8 encoding of this string, equivalent to
+    /// [`str::bytes`]. The string is encoded once by the VM, and the
+    /// bytes are read from there instead of being copied into a `Vec`
+    /// like [`as_bytes`](DString::as_bytes) does.
+    ///
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        dart_unwrap!(self.handle.string_utf8_bytes()).iter().copied()
+    }
+
     pub fn empty() -> Self {
         Self {
             handle: UnverifiedDartHandle::empty_string(),
@@ -53,6 +118,78 @@ impl DString {
     pub fn as_string(&self) -> String {
         dart_unwrap!(self.handle.string_to_utf8())
     }
+
+    ///
+    /// Copies the UTF-16 code units of this string into a `Vec`. This
+    /// is much cheaper than iterating over [`code_units`](DString::code_units),
+    /// which reads each element through the VM.
+    ///
+    pub fn code_units_vec(&self) -> Vec<u16> {
+        dart_unwrap!(self.handle.string_to_utf16())
+    }
+
+    ///
+    /// Checks if two strings are equal, ignoring the case of ASCII
+    /// characters. Non-ASCII characters must match exactly.
+    ///
+    pub fn eq_ignore_ascii_case(&self, other: &DString) -> bool {
+        self.as_string().eq_ignore_ascii_case(&other.as_string())
+    }
+
+    ///
+    /// Compares two strings after lowercasing them.
+    ///
+    /// Lowercasing is done in Rust using the Unicode rules of
+    /// [`str::to_lowercase`], and the strings are ordered by their
+    /// Unicode scalar values. This may differ from calling
+    /// `toLowerCase` and `compareTo` in Dart for strings containing
+    /// characters outside of the Basic Multilingual Plane.
+    ///
+    pub fn compare_ignore_case(&self, other: &DString) -> std::cmp::Ordering {
+        self.as_string()
+            .to_lowercase()
+            .cmp(&other.as_string().to_lowercase())
+    }
+
+    ///
+    /// Repeats this string `n` times, the same as `string * n` in
+    /// Dart.
+    ///
+    pub fn repeat(&self, n: usize) -> Self {
+        self.mul_by(Integer::from(n))
+    }
+
+    ///
+    /// Reverses this string by runes rather than by code units, so
+    /// surrogate pairs are kept intact.
+    ///
+    pub fn reversed(&self) -> Self {
+        let reversed = self.as_string().chars().rev().collect::<String>();
+        Self::new(&reversed)
+    }
+
+    ///
+    /// Counts the extended grapheme clusters (user perceived
+    /// characters) in this string.
+    ///
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn grapheme_count(&self) -> usize {
+        use unicode_segmentation::UnicodeSegmentation;
+        self.as_string().graphemes(true).count()
+    }
+
+    ///
+    /// Splits this string into its extended grapheme clusters (user
+    /// perceived characters).
+    ///
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn graphemes(&self) -> Vec<String> {
+        use unicode_segmentation::UnicodeSegmentation;
+        self.as_string()
+            .graphemes(true)
+            .map(String::from)
+            .collect()
+    }
 }
 
 thread_local! {
@@ -261,6 +398,45 @@ impl DString {
             .map(|x| Self::from_handle(x).ok().unwrap())
     }
 
+    ///
+    /// Replaces each match of `pattern` (a `String` or `RegExp`) with
+    /// the result of calling `replace` on the matched text.
+    ///
+    /// Matches are found using `pattern.allMatches` in Dart, while the
+    /// replacements and the resulting string are assembled in Rust.
+    ///
+    pub fn replace_with(
+        &self,
+        pattern: impl DartHandle,
+        mut replace: impl FnMut(&str) -> String,
+    ) -> Result<Self, Error> {
+        let matches = pattern
+            .safe_handle()
+            .invoke(
+                UnverifiedDartHandle::string_from_str("allMatches"),
+                &mut [self.handle],
+            )?
+            .invoke(UnverifiedDartHandle::string_from_str("toList"), &mut [])?;
+        let units = self.code_units_vec();
+        let mut result = Vec::with_capacity(units.len());
+        let mut last = 0;
+        for idx in 0..matches.list_length()? {
+            let m = matches.list_at(idx)?;
+            let start = m
+                .get_field(UnverifiedDartHandle::string_from_str("start"))?
+                .get_i64()? as usize;
+            let end = m
+                .get_field(UnverifiedDartHandle::string_from_str("end"))?
+                .get_i64()? as usize;
+            result.extend_from_slice(&units[last..start]);
+            let matched = String::from_utf16_lossy(&units[start..end]);
+            result.extend(replace(&matched).encode_utf16());
+            last = end;
+        }
+        result.extend_from_slice(&units[last..]);
+        Self::from_utf16(&result)
+    }
+
     pub fn replace_first(
         &self,
         from: impl DartHandle,
@@ -410,6 +586,32 @@ impl DString {
             .ok()
             .unwrap()
     }
+
+    ///
+    /// Parses this string as an integer using Dart's `int.tryParse`,
+    /// returning `None` should it not be a valid integer literal.
+    ///
+    pub fn parse_int(&self) -> Option<Integer> {
+        let class = dart_unwrap!(DartClass::new("dart:core", "int"));
+        let result = class.invoke(
+            UnverifiedDartHandle::string_from_str("tryParse"),
+            &mut [self.handle],
+        );
+        Integer::from_handle(dart_unwrap!(result)).ok()
+    }
+
+    ///
+    /// Parses this string as a double using Dart's `double.tryParse`,
+    /// returning `None` should it not be a valid double literal.
+    ///
+    pub fn parse_double(&self) -> Option<Double> {
+        let class = dart_unwrap!(DartClass::new("dart:core", "double"));
+        let result = class.invoke(
+            UnverifiedDartHandle::string_from_str("tryParse"),
+            &mut [self.handle],
+        );
+        Double::from_handle(dart_unwrap!(result)).ok()
+    }
 }
 
 impl Mul<Integer> for DString {
@@ -434,6 +636,30 @@ impl PartialEq<Self> for DString {
     }
 }
 
+impl PartialEq<str> for DString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_string() == other
+    }
+}
+
+impl PartialEq<&'_ str> for DString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_string() == *other
+    }
+}
+
+impl PartialEq<DString> for str {
+    fn eq(&self, other: &DString) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<DString> for &'_ str {
+    fn eq(&self, other: &DString) -> bool {
+        other == *self
+    }
+}
+
 impl Deref for DString {
     type Target = UnverifiedDartHandle;
     fn deref(&self) -> &Self::Target {
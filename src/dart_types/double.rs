@@ -1,7 +1,8 @@
-use crate::dart_handle::{DartHandle, UnverifiedDartHandle};
+use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
 use crate::dart_types::DartType;
 use crate::dart_unwrap;
 use std::cell::Cell;
+use std::cmp::Ordering;
 use std::ops::Deref;
 use std::thread::LocalKey;
 
@@ -20,6 +21,27 @@ impl Double {
         }
     }
 
+    ///
+    /// Creates a new dart `double.nan`.
+    ///
+    pub fn nan() -> Self {
+        Self::new(std::f64::NAN)
+    }
+
+    ///
+    /// Creates a new dart `double.infinity`.
+    ///
+    pub fn infinity() -> Self {
+        Self::new(std::f64::INFINITY)
+    }
+
+    ///
+    /// Creates a new dart `double.negativeInfinity`.
+    ///
+    pub fn neg_infinity() -> Self {
+        Self::new(std::f64::NEG_INFINITY)
+    }
+
     #[inline]
     pub fn value(&self) -> f64 {
         if let Some(x) = self.value.get() {
@@ -30,6 +52,92 @@ impl Double {
             value
         }
     }
+
+    ///
+    /// Compares this double to `other` using a total order, as per
+    /// [`f64::total_cmp`]. Unlike the [`PartialOrd`] implementation,
+    /// NaNs are ordered: positive NaNs above positive infinity and
+    /// negative NaNs below negative infinity. `-0.0` is also ordered
+    /// below `0.0`.
+    ///
+    pub fn total_cmp(&self, other: &Double) -> Ordering {
+        total_order(self.value(), other.value())
+    }
+
+    ///
+    /// Adds `other` to this double using Dart's `+` operator,
+    /// rather than computing the result in Rust as the
+    /// [`Add`](std::ops::Add) implementation does.
+    ///
+    /// This calls into the VM, so prefer the Rust operators unless
+    /// the exact Dart semantics matter.
+    ///
+    pub fn dart_add(&self, other: &Double) -> Result<Double, Error> {
+        self.invoke_dart("+", &mut [**other])
+    }
+
+    ///
+    /// Multiplies this double by `other` using Dart's `*` operator.
+    /// See [`dart_add`](Double::dart_add) for when to use this.
+    ///
+    pub fn dart_mul(&self, other: &Double) -> Result<Double, Error> {
+        self.invoke_dart("*", &mut [**other])
+    }
+
+    fn invoke_dart(&self, name: &str, args: &mut [UnverifiedDartHandle]) -> Result<Double, Error> {
+        self.handle
+            .invoke(UnverifiedDartHandle::string_from_str(name), args)
+            .map(|x| Double::from_handle(x).ok().unwrap())
+    }
+}
+
+///
+/// The order used by [`Double::total_cmp`], and therefore by
+/// [`OrdDouble`].
+///
+fn total_order(a: f64, b: f64) -> Ordering {
+    a.total_cmp(&b)
+}
+
+///
+/// A `Double` ordered by [`Double::total_cmp`], so that it implements
+/// [`Ord`] and can be sorted or used as a key in sorted collections
+/// even when it could be NaN.
+///
+#[derive(Clone, Debug)]
+pub struct OrdDouble(pub Double);
+
+impl PartialEq<Self> for OrdDouble {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrdDouble {}
+
+impl PartialOrd<Self> for OrdDouble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdDouble {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl From<Double> for OrdDouble {
+    fn from(value: Double) -> Self {
+        Self(value)
+    }
+}
+
+impl Deref for OrdDouble {
+    type Target = Double;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
 
 mod impls {
@@ -49,6 +157,12 @@ mod impls {
         Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
     };
 
+    impl Default for Double {
+        fn default() -> Self {
+            Self::new(0.0)
+        }
+    }
+
     impl PartialEq<Self> for Double {
         #[inline]
         fn eq(&self, other: &Self) -> bool {
@@ -337,3 +451,32 @@ unsafe impl DartHandle for Double {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::total_order;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn sorts_nans_and_zeroes() {
+        let nan = std::f64::NAN;
+        let inf = std::f64::INFINITY;
+        let mut values = [nan, 1.0, 0.0, -inf, -nan, -0.0, inf, -1.0];
+        values.sort_by(|a, b| total_order(*a, *b));
+        let sorted = values.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        let expected = [-nan, -inf, -1.0, -0.0, 0.0, 1.0, inf, nan]
+            .iter()
+            .map(|x| x.to_bits())
+            .collect::<Vec<_>>();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn nans_equal_themselves() {
+        let nan = std::f64::NAN;
+        assert_eq!(total_order(nan, nan), Ordering::Equal);
+        assert_eq!(total_order(-nan, nan), Ordering::Less);
+        assert_eq!(total_order(-0.0, 0.0), Ordering::Less);
+        assert_eq!(total_order(std::f64::INFINITY, nan), Ordering::Less);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
+use crate::dart_types::class::DartClass;
 use crate::dart_types::d_string::DString;
 use crate::dart_unwrap;
 
@@ -17,30 +18,147 @@ impl Dynamic {
             .invoke(function.safe_handle(), parameters)
             .map(Self::from)
     }
+    ///
+    /// Reads a field of this object, either directly or by calling
+    /// its getter. Equivalent to `object.field`.
+    ///
     pub fn get_field(&self, field: DString) -> Result<Dynamic, Error> {
         self.handle.get_field(field.safe_handle()).map(Self::from)
     }
+    ///
+    /// Sets a field of this object, either by assigning it directly or
+    /// by calling its setter. Equivalent to `object.field = value`.
+    ///
     pub fn set_field(&self, field: DString, value: UnverifiedDartHandle) -> Result<(), Error> {
         self.handle.set_field(field.safe_handle(), value)
     }
+    ///
+    /// Checks if this object has a field or getter called `name`.
+    ///
+    /// The embedding api has no way of reflecting over members, so
+    /// this reads the field and checks if it succeeded. Note that
+    /// this means getters will be run.
+    ///
+    pub fn has_field(&self, name: &str) -> bool {
+        self.handle
+            .get_field(UnverifiedDartHandle::string_from_str(name))
+            .map(|x| !x.is_tear_off())
+            .unwrap_or(false)
+    }
+    ///
+    /// Checks if this object has a method called `name`, by tearing
+    /// off the method and checking if it succeeded.
+    ///
+    pub fn has_method(&self, name: &str) -> bool {
+        self.handle
+            .get_field(UnverifiedDartHandle::string_from_str(name))
+            .map(|x| x.is_tear_off())
+            .unwrap_or(false)
+    }
+    ///
+    /// Reads a property of this object, which may be a field or a
+    /// getter. This is the same as [`get_field`](Dynamic::get_field),
+    /// whereas [`call_function`](Dynamic::call_function) should be used
+    /// to call a method without arguments.
+    ///
     pub fn get_property(&self, property: DString) -> Result<Dynamic, Error> {
-        self.call_function(property, &mut [])
+        self.get_field(property)
     }
+    ///
+    /// Sets a property of this object, which may be a field or a
+    /// setter. This is the same as [`set_field`](Dynamic::set_field),
+    /// whereas [`call_function`](Dynamic::call_function) should be used
+    /// to call a method which takes the value as an argument.
+    ///
     pub fn set_property(
         &self,
         property: DString,
         value: UnverifiedDartHandle,
     ) -> Result<(), Error> {
-        self.call_function(property, &mut [value]).map(drop)
+        self.set_field(property, value)
+    }
+    ///
+    /// Calls the `[]` operator, such as for reading an element of a
+    /// `List` or a value of a `Map`.
+    ///
+    pub fn get_index(&self, idx: impl DartHandle) -> Result<Dynamic, Error> {
+        self.handle.op_idx(idx.safe_handle()).map(Self::from)
+    }
+    ///
+    /// Calls the `[]=` operator, such as for setting an element of a
+    /// `List` or a value of a `Map`.
+    ///
+    pub fn set_index(&self, idx: impl DartHandle, value: impl DartHandle) -> Result<(), Error> {
+        self.handle.op_idx_assign(idx.safe_handle(), value.safe_handle())
     }
     pub fn get_type(&self) -> Dynamic {
         dart_unwrap!(self.handle.get_instance_type().map(DartHandle::from_handle))
             .ok()
             .unwrap()
     }
+    ///
+    /// Gets the name of the type of this object, as returned by
+    /// `runtimeType.toString()`, such as `double`.
+    ///
     pub fn type_name(&self) -> String {
-        let ty = self.get_type();
-        ty.to_string()
+        let name = self.handle.get_instance_type().and_then(|x| x.to_string());
+        dart_unwrap!(name).into_string().unwrap()
+    }
+    ///
+    /// Gets the name of the runtime class of this object. Note that
+    /// this is the implementation class, such as `_Double`, rather
+    /// than the interface it implements as returned by
+    /// [`type_name`](Dynamic::type_name).
+    ///
+    pub fn runtime_type_name(&self) -> String {
+        dart_unwrap!(self
+            .handle
+            .get_instance_type()
+            .and_then(|x| x.get_class_name())
+            .and_then(|x| x.string_to_utf8()))
+    }
+    ///
+    /// Creates a copy of this object, so that modifying the copy
+    /// doesn't modify this object. A `Dynamic` is only a handle, so
+    /// copying it refers to the same object.
+    ///
+    /// The following are supported:
+    /// - `null`, `bool`s, numbers and `String`s are immutable, and
+    ///   are returned as is.
+    /// - `List`s are copied using `toList`, and `Set`s using `toSet`.
+    /// - `Map`s are copied using `Map.from`.
+    /// - Objects with a `copyWith` method are copied by calling it
+    ///   without any arguments.
+    ///
+    /// Anything else returns an error. Note that elements are not
+    /// copied themselves.
+    ///
+    pub fn copy_object(&self) -> Result<Dynamic, Error> {
+        let handle = self.handle;
+        if handle.is_null()
+            || handle.is_boolean()
+            || handle.is_integer()
+            || handle.is_double()
+            || handle.is_string()
+        {
+            Ok(*self)
+        } else if handle.is_list() {
+            self.call_function(DString::new("toList"), &mut [])
+        } else if handle.is_map() {
+            let map = DartClass::new("dart:core", "Map")?;
+            map.new_of_type_self(Some(*DString::new("from")), &mut [handle])
+                .map(Self::from)
+        } else if handle.instanceof(*DartClass::new("dart:core", "Set")?)? {
+            self.call_function(DString::new("toSet"), &mut [])
+        } else if self.has_method("copyWith") {
+            self.call_function(DString::new("copyWith"), &mut [])
+        } else {
+            Err(Error::new_api(&format!(
+                "Objects of type `{}` cannot be copied.",
+                self.runtime_type_name()
+            ))
+            .unwrap())
+        }
     }
     pub fn call_as_function(
         &self,
@@ -0,0 +1,100 @@
+use crate::dart_handle::{DartHandle, Error, ReceivePort, UnverifiedDartHandle};
+use crate::dart_types::closure::Closure;
+use dart_sys as ffi;
+use std::ffi::CString;
+use std::ops::Deref;
+
+///
+/// A Dart [`Future`](https://api.dart.dev/stable/2.7.1/dart-async/Future-class.html).
+///
+#[derive(Copy, Clone)]
+pub struct Future {
+    handle: UnverifiedDartHandle,
+}
+
+impl Future {
+    ///
+    /// Registers `on_value` to be called with the value of this future
+    /// once it completes, returning the future of its result.
+    ///
+    pub fn then(&self, on_value: Closure) -> Result<Future, Error> {
+        self.invoke_future("then", on_value)
+    }
+
+    ///
+    /// Registers `on_error` to be called with the error of this future
+    /// should it complete with one, returning a future which completes
+    /// with either the value of this future or the result of `on_error`.
+    ///
+    pub fn catch_error(&self, on_error: Closure) -> Result<Future, Error> {
+        self.invoke_future("catchError", on_error)
+    }
+
+    ///
+    /// Creates a native port called `name` which is sent the value of
+    /// this future once it completes, calling `handler` with it as
+    /// a message. This bridges a Dart future to native code without
+    /// having to pass a `SendPort` through Dart.
+    ///
+    /// The value must be something that can be sent to a native port,
+    /// such as `null`, a number, a string, or a list of those. Should
+    /// the future complete with an error, or with a value that can't be
+    /// sent, `handler` is never called.
+    ///
+    /// The port stays open until the returned [`ReceivePort`] is
+    /// closed.
+    ///
+    /// # Safety
+    /// The same as [`ReceivePort::new`].
+    ///
+    pub unsafe fn complete_with_port(
+        &self,
+        name: CString,
+        handler: unsafe extern "C" fn(
+            dest_port_id: ffi::Dart_Port,
+            message: *mut ffi::Dart_CObject,
+        ),
+    ) -> Result<ReceivePort, Error> {
+        let port = ReceivePort::new(name, handler)?;
+        let send = port
+            .send_port()
+            .get_field(UnverifiedDartHandle::string_from_str("send"))?;
+        let send = Closure::from_handle(send).ok().unwrap();
+        if let Err(e) = self.then(send) {
+            port.close();
+            return Err(e);
+        }
+        Ok(port)
+    }
+
+    fn invoke_future(&self, name: &str, arg: Closure) -> Result<Future, Error> {
+        let handle = self.handle.invoke(
+            UnverifiedDartHandle::string_from_str(name),
+            &mut [arg.safe_handle()],
+        )?;
+        Ok(Self { handle })
+    }
+}
+
+impl Deref for Future {
+    type Target = UnverifiedDartHandle;
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+unsafe impl DartHandle for Future {
+    fn handle(&self) -> dart_sys::Dart_Handle {
+        self.handle.handle()
+    }
+    fn safe_handle(&self) -> UnverifiedDartHandle {
+        self.handle
+    }
+    fn from_handle(handle: UnverifiedDartHandle) -> Result<Self, UnverifiedDartHandle> {
+        if handle.is_future() {
+            Ok(Self { handle })
+        } else {
+            Err(handle)
+        }
+    }
+}
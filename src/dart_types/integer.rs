@@ -1,4 +1,4 @@
-use crate::dart_handle::{DartHandle, UnverifiedDartHandle};
+use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
 use crate::dart_types::DartType;
 use crate::dart_unwrap;
 use std::cell::Cell;
@@ -36,6 +36,155 @@ impl Integer {
             .into_string()
             .unwrap()
     }
+
+    ///
+    /// Returns the least significant `width` bits of this integer as
+    /// an unsigned integer. Equivalent to Dart's `toUnsigned`, and as
+    /// such a `width` of 0 gives 0, while a `width` of 64 or more gives
+    /// the integer back unchanged.
+    ///
+    pub fn to_unsigned(&self, width: u32) -> Integer {
+        if !self.fits_in_i64() {
+            dart_unwrap!(self.invoke_dart("toUnsigned", &mut [*Integer::from(width)]))
+        } else if width == 0 {
+            Integer::new(0)
+        } else if width >= 64 {
+            self.clone()
+        } else {
+            Integer::new((self.value() as u64 & (u64::MAX >> (64 - width))) as i64)
+        }
+    }
+
+    ///
+    /// Returns the least significant `width` bits of this integer,
+    /// sign extended from the highest of those bits. Equivalent to
+    /// Dart's `toSigned`, and as such a `width` of 64 or more gives
+    /// the integer back unchanged.
+    ///
+    /// Returns the error thrown by Dart should `width` be 0.
+    ///
+    pub fn to_signed(&self, width: u32) -> Result<Integer, Error> {
+        if width == 0 || !self.fits_in_i64() {
+            self.invoke_dart("toSigned", &mut [*Integer::from(width)])
+        } else if width >= 64 {
+            Ok(self.clone())
+        } else {
+            let shift = 64 - width;
+            Ok(Integer::new((self.value() << shift) >> shift))
+        }
+    }
+
+    ///
+    /// The minimum number of bits required to store this integer,
+    /// excluding the sign bit. Equivalent to Dart's `bitLength`.
+    ///
+    pub fn bit_length(&self) -> i64 {
+        if self.fits_in_i64() {
+            let value = self.value();
+            let value = if value < 0 { !value } else { value };
+            (64 - value.leading_zeros()) as i64
+        } else {
+            dart_unwrap!(self.invoke_dart("bitLength", &mut [])).value()
+        }
+    }
+
+    ///
+    /// Returns `self` to the power of `exponent` modulo `modulus`.
+    /// Equivalent to Dart's `modPow`, and as such `exponent` must
+    /// be non-negative and `modulus` must be positive, otherwise
+    /// the error thrown by Dart is returned.
+    ///
+    pub fn mod_pow(&self, exponent: Integer, modulus: Integer) -> Result<Integer, Error> {
+        let fits = self.fits_in_i64() && exponent.fits_in_i64() && modulus.fits_in_i64();
+        if fits && exponent.value() >= 0 && modulus.value() > 0 {
+            let modulus = modulus.value() as i128;
+            let mut exponent = exponent.value();
+            let mut base = (self.value() as i128).rem_euclid(modulus);
+            let mut result = 1 % modulus;
+            while exponent > 0 {
+                if exponent & 1 == 1 {
+                    result = result * base % modulus;
+                }
+                base = base * base % modulus;
+                exponent >>= 1;
+            }
+            Ok(Integer::new(result as i64))
+        } else {
+            self.invoke_dart("modPow", &mut [*exponent, *modulus])
+        }
+    }
+
+    ///
+    /// Formats this integer in base `radix` using lowercase letters
+    /// for digits above 9, the same as Dart's `toRadixString`.
+    ///
+    /// # Panics
+    /// Panics should `radix` not be in the range `2..=36`.
+    ///
+    pub fn to_radix_string(&self, radix: u32) -> String {
+        assert!(
+            (2..=36).contains(&radix),
+            "to_radix_string: radix must be in the range 2..=36, got {}",
+            radix
+        );
+        if !self.fits_in_i64() {
+            let handle = self.handle.invoke(
+                UnverifiedDartHandle::string_from_str("toRadixString"),
+                &mut [*Integer::from(radix)],
+            );
+            return dart_unwrap!(dart_unwrap!(handle).string_to_utf8());
+        }
+        let value = self.value();
+        let mut magnitude = (value as i128).abs() as u64;
+        let mut digits = Vec::new();
+        loop {
+            digits.push(std::char::from_digit((magnitude % radix as u64) as u32, radix).unwrap());
+            magnitude /= radix as u64;
+            if magnitude == 0 {
+                break;
+            }
+        }
+        if value < 0 {
+            digits.push('-');
+        }
+        digits.into_iter().rev().collect()
+    }
+
+    ///
+    /// Adds `other` to this integer using Dart's `+` operator.
+    ///
+    /// Unlike the [`Add`](std::ops::Add) implementation, which computes
+    /// the result in Rust, this calls into the VM and so follows
+    /// Dart's semantics exactly, for example wrapping on overflow
+    /// where Rust would panic in debug builds. Prefer the Rust
+    /// operators unless those semantics matter, as they are much
+    /// cheaper.
+    ///
+    pub fn dart_add(&self, other: &Integer) -> Result<Integer, Error> {
+        self.invoke_dart("+", &mut [**other])
+    }
+
+    ///
+    /// Multiplies this integer by `other` using Dart's `*` operator.
+    /// See [`dart_add`](Integer::dart_add) for when to use this.
+    ///
+    pub fn dart_mul(&self, other: &Integer) -> Result<Integer, Error> {
+        self.invoke_dart("*", &mut [**other])
+    }
+
+    fn fits_in_i64(&self) -> bool {
+        self.value.get().is_some() || dart_unwrap!(self.handle.integer_fits_in_i64())
+    }
+
+    fn invoke_dart(
+        &self,
+        name: &str,
+        args: &mut [UnverifiedDartHandle],
+    ) -> Result<Integer, Error> {
+        self.handle
+            .invoke(UnverifiedDartHandle::string_from_str(name), args)
+            .map(|x| Integer::from_handle(x).ok().unwrap())
+    }
 }
 
 mod impls {
@@ -57,6 +206,12 @@ mod impls {
         SubAssign,
     };
 
+    impl Default for Integer {
+        fn default() -> Self {
+            Self::new(0)
+        }
+    }
+
     impl PartialEq<Self> for Integer {
         #[inline]
         fn eq(&self, other: &Self) -> bool {
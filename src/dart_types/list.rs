@@ -1,4 +1,9 @@
-use crate::dart_handle::{DartHandle, Error, TypedData, UnverifiedDartHandle};
+use crate::dart_cobject::CObject;
+use crate::dart_handle::{
+    DartHandle, Error, SendPort, TypedData, TypedDataGuard, UnverifiedDartHandle,
+};
+use crate::dart_types::boolean::Boolean;
+use crate::dart_types::d_string::DString;
 use crate::dart_types::double::Double;
 use crate::dart_types::integer::Integer;
 use crate::dart_types::DartType;
@@ -23,6 +28,432 @@ impl<T: DartType> List<T> {
             _phantom: PhantomData,
         }
     }
+
+    ///
+    /// Creates a list from a handle, verifying that every element
+    /// of the list is a `T`.
+    ///
+    /// [`from_handle`](DartHandle::from_handle) only checks that the
+    /// handle is a list, so a mistyped list will only be noticed once
+    /// an element is accessed. This instead catches the mismatch at the
+    /// boundary.
+    ///
+    /// Since only the elements are checked, an empty list is accepted
+    /// whatever its element type, so a later insertion may still fail.
+    ///
+    /// # Performance
+    /// The embedding api does not expose a list's type arguments, so
+    /// this reads every element of the list, making it `O(n)` calls
+    /// into the VM. Prefer `from_handle` when the element type is
+    /// already known.
+    ///
+    pub fn from_handle_checked(
+        handle: UnverifiedDartHandle,
+    ) -> Result<Self, UnverifiedDartHandle> {
+        let list = Self::from_handle(handle)?;
+        let len = list.handle.list_length().map_err(|_| handle)?;
+        for idx in 0..len {
+            let item = list.handle.list_at(idx).map_err(|_| handle)?;
+            if T::from_handle(item).is_err() {
+                return Err(handle);
+            }
+        }
+        Ok(list)
+    }
+
+    ///
+    /// Creates an iterator which lazily reads each element of the
+    /// list in order.
+    ///
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            start: 0,
+            end: self.length(),
+        }
+    }
+
+    ///
+    /// Creates an iterator which lazily reads each element of the list
+    /// along with its index. As with [`iter`](List::iter), the length
+    /// of the list is read once when the iterator is created.
+    ///
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (usize, T)> + '_ {
+        self.iter().enumerate()
+    }
+
+    ///
+    /// Creates an iterator over at most the first `n` elements of
+    /// the list. Elements are read lazily, and a `n` beyond the length
+    /// of the list stops at its end.
+    ///
+    pub fn take(&self, n: usize) -> Iter<'_, T> {
+        let mut iter = self.iter();
+        iter.end = iter.end.min(n);
+        iter
+    }
+
+    ///
+    /// Creates an iterator over all but the first `n` elements of
+    /// the list. Elements are read lazily, and a `n` beyond the length
+    /// of the list yields nothing.
+    ///
+    pub fn skip(&self, n: usize) -> Iter<'_, T> {
+        let mut iter = self.iter();
+        iter.start = iter.end.min(n);
+        iter
+    }
+
+    ///
+    /// Sets the element at `idx`, returning the element which was
+    /// previously there. Returns an error without modifying the list
+    /// should `idx` be out of bounds.
+    ///
+    pub fn replace_at(&mut self, idx: usize, item: T) -> Result<T, Error> {
+        let old = self.get_at(idx)?;
+        self.set_at(idx, item)?;
+        Ok(old)
+    }
+
+    ///
+    /// Sets the element at `idx` like [`set_at`](ListLike::set_at),
+    /// first checking that `item` is an instance of the list's element
+    /// type and returning a descriptive error should it not be.
+    ///
+    /// Lists whose element type is `dynamic` accept any item. The
+    /// element type is found using `dart:mirrors`, see
+    /// [`list_element_type`](UnverifiedDartHandle::list_element_type).
+    /// Should mirrors be unavailable, such as in AOT compiled code, the
+    /// check is skipped and this behaves like `set_at`.
+    ///
+    pub fn set_at_checked(&mut self, idx: usize, item: T) -> Result<(), Error> {
+        if let Ok(Some(ty)) = self.handle.list_element_type() {
+            let item = item.safe_handle();
+            if !item.instanceof(ty)? {
+                return Err(Error::new_api_fmt(format_args!(
+                    "Cannot store a `{}` in a list of `{}`.",
+                    item.get_instance_type()?.to_string()?.to_string_lossy(),
+                    ty.to_string()?.to_string_lossy()
+                )));
+            }
+        }
+        self.set_at(idx, item)
+    }
+
+    ///
+    /// Gets the element at `idx`, or `None` should it be out of
+    /// bounds. Unlike [`get_at`](ListLike::get_at), the bounds are
+    /// checked before calling into the VM, so no `RangeError` is thrown.
+    ///
+    pub fn get_opt(&self, idx: usize) -> Option<T> {
+        if idx < self.length() {
+            self.get_at(idx).ok()
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Creates an iterator over all overlapping windows of `size`
+    /// elements, advancing by one element each step. This mirrors
+    /// [`slice::windows`], and yields nothing should `size` be greater
+    /// than the length of the list.
+    ///
+    /// # Panics
+    /// Panics should `size` be zero.
+    ///
+    pub fn windows(&self, size: usize) -> impl Iterator<Item = Vec<T>> + '_ {
+        assert!(size != 0, "windows: size must be non-zero");
+        let count = (self.length() + 1).saturating_sub(size);
+        (0..count).map(move |start| {
+            (start..start + size)
+                .map(|idx| dart_unwrap!(self.get_at(idx)))
+                .collect()
+        })
+    }
+
+    ///
+    /// Divides the list into two views at `mid`, the first covering
+    /// `[0, mid)` and the second `[mid, len)`. This mirrors
+    /// [`slice::split_at`].
+    ///
+    /// # Panics
+    /// Panics should `mid` be greater than the length of the list.
+    ///
+    pub fn split_at(&self, mid: usize) -> (ListView<'_, T>, ListView<'_, T>) {
+        let len = self.length();
+        assert!(
+            mid <= len,
+            "split_at: mid ({}) is greater than the length ({})",
+            mid,
+            len
+        );
+        (ListView::new(0, mid, self), ListView::new(mid, len - mid, self))
+    }
+
+    ///
+    /// Gets the `n`th element counting from the end of the list, where
+    /// `get_from_end(1)` is the last element. Returns an error should
+    /// `n` be zero or greater than the length of the list.
+    ///
+    pub fn get_from_end(&self, n: usize) -> Result<T, Error> {
+        let len = self.length();
+        if n == 0 || n > len {
+            return Err(Error::new_api(&format!(
+                "Index {} from the end is out of bounds for a list of length {}.",
+                n, len
+            ))
+            .unwrap());
+        }
+        self.get_at(len - n)
+    }
+
+    ///
+    /// Gets the element at `idx`, or `default` should it be out of
+    /// bounds. See [`get_opt`](List::get_opt).
+    ///
+    pub fn get_or(&self, idx: usize, default: T) -> T {
+        self.get_opt(idx).unwrap_or(default)
+    }
+
+    ///
+    /// Inserts `item` at `idx`, shifting all following elements up
+    /// by one. An `idx` equal to the length of the list appends the
+    /// item.
+    ///
+    /// Returns an error should `idx` be greater than the length, or
+    /// should the list not be growable.
+    ///
+    pub fn insert(&mut self, idx: usize, item: T) -> Result<(), Error> {
+        self.handle
+            .invoke(
+                UnverifiedDartHandle::string_from_str("insert"),
+                &mut [*Integer::from(idx), item.safe_handle()],
+            )
+            .map(drop)
+    }
+
+    ///
+    /// Shuffles the elements of this list in place using `rng`. As
+    /// the length of the list is unchanged, this also works on fixed
+    /// length lists.
+    ///
+    #[cfg(feature = "rand")]
+    pub fn shuffle(&mut self, rng: &mut impl rand::Rng) -> Result<(), Error> {
+        for i in (1..self.length()).rev() {
+            let j = rng.gen_range(0, i + 1);
+            if i != j {
+                let a = self.get_at(i)?;
+                let b = self.get_at(j)?;
+                self.set_at(i, b)?;
+                self.set_at(j, a)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Finds the index of the first element matching `pred`, reading
+    /// elements only until a match is found.
+    ///
+    pub fn position(&self, mut pred: impl FnMut(&T) -> bool) -> Option<usize> {
+        self.iter().position(|x| pred(&x))
+    }
+
+    ///
+    /// Finds the first element matching `pred`, reading elements only
+    /// until a match is found.
+    ///
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<T> {
+        self.iter().find(|x| pred(x))
+    }
+
+    ///
+    /// Reads the elements in reverse order into a `Vec`. Unlike
+    /// [`reversed`](List::reversed), this doesn't create a Dart
+    /// iterable.
+    ///
+    pub fn reversed_vec(&self) -> Vec<T> {
+        self.iter().rev().collect()
+    }
+
+    ///
+    /// Checks if the list contains an element equal to `item` using
+    /// Dart's `==`. Equivalent to Dart's `contains`.
+    ///
+    pub fn contains(&self, item: &T) -> bool {
+        let handle = self.handle.invoke(
+            UnverifiedDartHandle::string_from_str("contains"),
+            &mut [item.safe_handle()],
+        );
+        Boolean::from_handle(dart_unwrap!(handle)).ok().unwrap().value()
+    }
+
+    ///
+    /// Checks if every one of `items` is contained in the list, as
+    /// described in [`contains`](List::contains). Duplicates in
+    /// `items` need not appear as many times in the list.
+    ///
+    pub fn contains_all(&self, items: &[T]) -> bool {
+        items.iter().all(|x| self.contains(x))
+    }
+
+    ///
+    /// Checks if every element of this list is contained in `other`,
+    /// as described in [`contains_all`](List::contains_all).
+    ///
+    pub fn is_subset_of(&self, other: &List<T>) -> bool {
+        self.all(|x| other.contains(x))
+    }
+
+    ///
+    /// Checks if any element matches `pred`, reading elements only
+    /// until a match is found. Equivalent to Dart's `any`.
+    ///
+    pub fn any(&self, mut pred: impl FnMut(&T) -> bool) -> bool {
+        self.iter().any(|x| pred(&x))
+    }
+
+    ///
+    /// Checks if every element matches `pred`, reading elements only
+    /// until one doesn't. Equivalent to Dart's `every`.
+    ///
+    pub fn all(&self, mut pred: impl FnMut(&T) -> bool) -> bool {
+        self.iter().all(|x| pred(&x))
+    }
+
+    ///
+    /// Gets the first element matching `pred`. Equivalent to Dart's
+    /// `firstWhere`, but returns `None` instead of throwing should no
+    /// element match. This is the same as [`find`](List::find).
+    ///
+    pub fn first_where(&self, pred: impl FnMut(&T) -> bool) -> Option<T> {
+        self.find(pred)
+    }
+
+    ///
+    /// Folds every element into an accumulator in order, starting
+    /// from `init`. This mirrors [`Iterator::fold`].
+    ///
+    pub fn fold<B>(&self, init: B, f: impl FnMut(B, T) -> B) -> B {
+        self.iter().fold(init, f)
+    }
+
+    ///
+    /// Reduces the elements to a single one by repeatedly applying
+    /// `f`, returning `None` should the list be empty. This mirrors
+    /// `Iterator::reduce`.
+    ///
+    pub fn reduce(&self, mut f: impl FnMut(T, T) -> T) -> Option<T> {
+        let mut items = self.iter();
+        let first = items.next()?;
+        Some(items.fold(first, |acc, x| f(acc, x)))
+    }
+
+    ///
+    /// Removes consecutive repeated elements, as determined by Dart's
+    /// `==`. This mirrors [`Vec::dedup`].
+    ///
+    /// The list is read into Rust, deduplicated and written back,
+    /// shrinking the list. As such this returns an error for fixed
+    /// length lists should any duplicates be found, in which case the
+    /// list is left untouched.
+    ///
+    pub fn dedup(&mut self) -> Result<(), Error> {
+        let mut kept: Vec<T> = Vec::new();
+        for item in self.iter() {
+            if let Some(last) = kept.last() {
+                if last.safe_handle().equals(item.safe_handle())? {
+                    continue;
+                }
+            }
+            kept.push(item);
+        }
+        if kept.len() == self.length() {
+            return Ok(());
+        }
+        self.rewrite(kept)
+    }
+
+    ///
+    /// Removes all repeated elements, keeping the first occurrence of
+    /// each. See [`dedup`](List::dedup) for more information.
+    ///
+    pub fn dedup_all(&mut self) -> Result<(), Error> {
+        let mut kept: Vec<T> = Vec::new();
+        'items: for item in self.iter() {
+            for other in &kept {
+                if other.safe_handle().equals(item.safe_handle())? {
+                    continue 'items;
+                }
+            }
+            kept.push(item);
+        }
+        if kept.len() == self.length() {
+            return Ok(());
+        }
+        self.rewrite(kept)
+    }
+
+    ///
+    /// Replaces the contents of the list with `items`, resizing it
+    /// should the length differ.
+    ///
+    fn rewrite(&mut self, items: Vec<T>) -> Result<(), Error> {
+        if items.len() != self.length() {
+            self.handle.set_field(
+                UnverifiedDartHandle::string_from_str("length"),
+                *Integer::from(items.len()),
+            )?;
+        }
+        for (idx, item) in items.into_iter().enumerate() {
+            self.handle.list_set_at(item.safe_handle(), idx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: DartType + PartialOrd> List<T> {
+    ///
+    /// Finds the index of the greatest element, or `None` should the
+    /// list be empty. Should several elements be equally great, the
+    /// index of the first is returned. Elements which can't be
+    /// compared, such as NaNs, never replace the greatest element
+    /// found so far, but are kept should they come first.
+    ///
+    pub fn position_max(&self) -> Option<usize> {
+        self.position_by(|x, best| x > best)
+    }
+
+    ///
+    /// Finds the index of the least element. See
+    /// [`position_max`](List::position_max) for how ties and
+    /// incomparable elements are handled.
+    ///
+    pub fn position_min(&self) -> Option<usize> {
+        self.position_by(|x, best| x < best)
+    }
+
+    fn position_by(&self, mut replaces: impl FnMut(&T, &T) -> bool) -> Option<usize> {
+        let mut best: Option<(usize, T)> = None;
+        for (idx, item) in self.iter_indexed() {
+            match &best {
+                Some((_, x)) if !replaces(&item, x) => {}
+                _ => best = Some((idx, item)),
+            }
+        }
+        best.map(|(idx, _)| idx)
+    }
+}
+
+impl<T: DartType + Clone> List<T> {
+    ///
+    /// Creates a mutable view over the entire list. This is the
+    /// same as calling `slice_mut(..)`.
+    ///
+    pub fn view_mut(&mut self) -> ListViewMut<'_, T> {
+        self.slice_mut(..)
+    }
 }
 
 impl List<UnverifiedDartHandle> {
@@ -33,6 +464,23 @@ impl List<UnverifiedDartHandle> {
             _phantom: PhantomData,
         }
     }
+
+    ///
+    /// Creates a list from the elements of a received
+    /// [`CObject::Array`], converting each using
+    /// [`CObject::to_handle`].
+    ///
+    pub fn from_cobject_array(items: &[CObject]) -> Result<Self, Error> {
+        let handle =
+            UnverifiedDartHandle::new_list_of(items.len(), ffi::Dart_CoreType_Id::Dynamic)?;
+        for (idx, item) in items.iter().enumerate() {
+            handle.list_set_at(item.to_handle()?, idx)?;
+        }
+        Ok(Self {
+            handle,
+            _phantom: PhantomData,
+        })
+    }
 }
 
 impl<T: TypedData> List<T> {
@@ -40,6 +488,41 @@ impl<T: TypedData> List<T> {
         let handle = UnverifiedDartHandle::new_external_typed_data_with_drop(data);
         Self::from_handle(dart_unwrap!(handle)).ok().unwrap()
     }
+
+    ///
+    /// Acquires direct access to the elements of this list, if it is
+    /// backed by typed data whose element type is `T`. Returns `None`
+    /// for any other kind of list.
+    ///
+    /// See [`TypedDataGuard`] for the restrictions which apply while
+    /// the data is acquired.
+    ///
+    pub fn as_typed_data(&self) -> Option<TypedDataGuard<'_, T>> {
+        if !self.handle.is_typed_data() {
+            return None;
+        }
+        dart_unwrap!(TypedDataGuard::acquire(&self.handle))
+    }
+}
+
+///
+/// Returns `$f` called with the elements of `$list` converted to
+/// `$out`, should the list be typed data of one of the given types.
+///
+macro_rules! with_typed_data {
+    ($list:expr, $f:ident, $out:ty, [$($T:ident => $t:ty),*]) => {
+        if $list.handle.is_typed_data() {
+            match $list.handle.typed_data_get_type() {
+                $(ffi::Dart_TypedData_Type::$T => {
+                    let data = dart_unwrap!(TypedDataGuard::<$t>::acquire(&$list.handle));
+                    if let Some(data) = data {
+                        return $f(&mut data.iter().map(|&x| x as $out));
+                    }
+                })*
+                _ => {}
+            }
+        }
+    };
 }
 
 impl List<Integer> {
@@ -47,6 +530,113 @@ impl List<Integer> {
         let handle = UnverifiedDartHandle::new_list_of(len, ffi::Dart_CoreType_Id::Int);
         Self::from_handle(dart_unwrap!(handle)).ok().unwrap()
     }
+
+    ///
+    /// Sums the elements of the list in Rust, wrapping on overflow
+    /// like Dart's `int`. An empty list sums to `0`.
+    ///
+    pub fn sum(&self) -> i64 {
+        self.with_values(|x| x.fold(0, i64::wrapping_add))
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.with_values(|x| x.min())
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.with_values(|x| x.max())
+    }
+
+    ///
+    /// Calls `f` with the values of the list, read directly from its
+    /// backing store should it be integer typed data, and through the
+    /// VM one element at a time otherwise.
+    ///
+    fn with_values<R>(&self, f: impl FnOnce(&mut dyn Iterator<Item = i64>) -> R) -> R {
+        with_typed_data!(self, f, i64, [
+            Int8 => i8,
+            Uint8 => u8,
+            Uint8Clamped => u8,
+            Int16 => i16,
+            Uint16 => u16,
+            Int32 => i32,
+            Uint32 => u32,
+            Int64 => i64,
+            Uint64 => u64
+        ]);
+        f(&mut self.iter().map(|x| x.value()))
+    }
+}
+
+impl List<Double> {
+    ///
+    /// Sums the elements of the list in Rust. An empty list sums to `0.0`.
+    ///
+    pub fn sum(&self) -> f64 {
+        self.with_values(|x| x.sum())
+    }
+
+    ///
+    /// Finds the smallest element. `NaN`s are ignored unless every
+    /// element is `NaN`, as with [`f64::min`].
+    ///
+    pub fn min(&self) -> Option<f64> {
+        self.with_values(|x| {
+            x.fold(None, |acc, x| Some(acc.map_or(x, |acc: f64| acc.min(x))))
+        })
+    }
+
+    ///
+    /// Finds the largest element. `NaN`s are ignored unless every
+    /// element is `NaN`, as with [`f64::max`].
+    ///
+    pub fn max(&self) -> Option<f64> {
+        self.with_values(|x| {
+            x.fold(None, |acc, x| Some(acc.map_or(x, |acc: f64| acc.max(x))))
+        })
+    }
+
+    ///
+    /// Calls `f` with the values of the list, read directly from its
+    /// backing store should it be floating point typed data, and
+    /// through the VM one element at a time otherwise.
+    ///
+    fn with_values<R>(&self, f: impl FnOnce(&mut dyn Iterator<Item = f64>) -> R) -> R {
+        with_typed_data!(self, f, f64, [Float32 => f32, Float64 => f64]);
+        f(&mut self.iter().map(|x| x.value()))
+    }
+}
+
+impl List<Boolean> {
+    ///
+    /// Creates a `List<bool>` holding `values`. Dart has no typed data
+    /// for booleans, so this is a regular list of objects.
+    ///
+    pub fn from_vec(values: Vec<bool>) -> Self {
+        let list = Self::new(values.len());
+        for (idx, value) in values.into_iter().enumerate() {
+            dart_unwrap!(list.handle.list_set_at(*Boolean::new(value), idx));
+        }
+        list
+    }
+
+    pub fn to_bool_vec(&self) -> Vec<bool> {
+        self.iter().map(|x| x.value()).collect()
+    }
+}
+
+impl List<DString> {
+    pub fn join(&self, separator: &str) -> DString {
+        let handle = self.handle.invoke(
+            UnverifiedDartHandle::string_from_str("join"),
+            &mut [UnverifiedDartHandle::string_from_str(separator)],
+        );
+        DString::from_handle(dart_unwrap!(handle)).ok().unwrap()
+    }
+
+    pub fn to_vec_strings(&self) -> Vec<String> {
+        self.iter().map(|x| x.as_string()).collect()
+    }
 }
 
 impl List<String> {
@@ -61,6 +651,38 @@ impl<T> List<T> {
         dart_unwrap!(self.handle.list_length())
     }
 
+    ///
+    /// Copies this list into a [`CObject::Array`], which may then be
+    /// posted to a port. Nested lists are converted recursively. See
+    /// [`CObject::from_handle`] for which elements are supported.
+    ///
+    pub fn to_cobject(&self) -> Result<CObject, Error> {
+        CObject::from_handle(self.handle)
+    }
+
+    ///
+    /// Creates a new Dart list containing copies of the elements of
+    /// this one.
+    ///
+    /// A `List` is only a handle, so copying or cloning it refers to
+    /// the same Dart list, and modifications through one are visible
+    /// through the other. The list returned here is a separate list,
+    /// and so are any lists nested within it, recursively. A list
+    /// which (indirectly) contains itself is copied only once. Other
+    /// elements, such as maps or instances of classes, are shared
+    /// with this list.
+    ///
+    /// The copy has the same type as this list, using Dart's
+    /// `sublist`.
+    ///
+    pub fn deep_clone(&self) -> List<T> {
+        let handle = deep_clone_list(self.handle, &mut Vec::new());
+        Self {
+            handle: dart_unwrap!(handle),
+            _phantom: PhantomData,
+        }
+    }
+
     pub fn get_range(&self, range: impl std::ops::RangeBounds<usize>) -> Result<Self, Error> {
         self.handle.list_get_range(range).map(|handle| Self {
             handle,
@@ -68,6 +690,43 @@ impl<T> List<T> {
         })
     }
 
+    ///
+    /// Splits the list into consecutive sublists of `size` elements,
+    /// the last of which may be shorter. Each sublist is a copy made
+    /// using [`get_range`](List::get_range), equivalent to
+    /// [`slice::chunks`].
+    ///
+    /// # Panics
+    /// Panics should `size` be 0.
+    ///
+    pub fn chunks(&self, size: usize) -> impl Iterator<Item = Result<Self, Error>> + '_ {
+        assert!(size != 0, "Chunk size must be non-zero");
+        (0..self.length())
+            .step_by(size)
+            .map(move |start| self.get_range(start..(start + size).min(self.length())))
+    }
+
+    ///
+    /// Posts the list to `port` in chunks of `chunk_size` elements,
+    /// each converted using [`to_cobject`](List::to_cobject), followed
+    /// by a [`CObject::Null`] to mark the end. This avoids building a
+    /// single large message for big lists.
+    ///
+    /// Returns `Ok(false)` should posting a chunk fail, in which case
+    /// no more chunks nor the terminating `null` are posted.
+    ///
+    /// # Panics
+    /// Panics should `chunk_size` be 0.
+    ///
+    pub fn post_chunks(&self, port: &SendPort, chunk_size: usize) -> Result<bool, Error> {
+        for chunk in self.chunks(chunk_size) {
+            if !port.post_cobject(chunk?.to_cobject()?) {
+                return Ok(false);
+            }
+        }
+        Ok(port.post_cobject(CObject::Null))
+    }
+
     pub fn iterator(&self) -> Result<UnverifiedDartHandle, Error> {
         self.handle
             .invoke(UnverifiedDartHandle::string_from_str("iterator"), &mut [])
@@ -79,6 +738,59 @@ impl<T> List<T> {
     }
 }
 
+///
+/// A lazy iterator over the elements of a [`List`], created by
+/// [`List::iter`], [`List::take`] and [`List::skip`].
+///
+/// The length of the list is read once when the iterator is created.
+///
+pub struct Iter<'a, T> {
+    list: &'a List<T>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, T: DartType> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start < self.end {
+            let item = dart_unwrap!(self.list.get_at(self.start));
+            self.start += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: DartType> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.start < self.end {
+            self.end -= 1;
+            Some(dart_unwrap!(self.list.get_at(self.end)))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: DartType> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T: DartType> IntoIterator for &'a List<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
 unsafe impl<T: 'static> DartHandle for List<T> {
     fn handle(&self) -> dart_sys::Dart_Handle {
         self.handle.handle()
@@ -98,6 +810,42 @@ unsafe impl<T: 'static> DartHandle for List<T> {
     }
 }
 
+///
+/// Copies `list` and every list nested within it. `copies` holds the
+/// lists copied so far along with their copies, such that a list which
+/// contains itself refers to its copy rather than being copied again.
+///
+fn deep_clone_list(
+    list: UnverifiedDartHandle,
+    copies: &mut Vec<(UnverifiedDartHandle, UnverifiedDartHandle)>,
+) -> Result<UnverifiedDartHandle, Error> {
+    let copy = list.invoke(
+        UnverifiedDartHandle::string_from_str("sublist"),
+        &mut [*Integer::new(0)],
+    )?;
+    // Typed data can only hold numbers.
+    if copy.is_typed_data() {
+        return Ok(copy);
+    }
+    copies.push((list, copy));
+    for idx in 0..copy.list_length()? {
+        let item = copy.list_at(idx)?;
+        if !item.is_list() {
+            continue;
+        }
+        let copied = copies
+            .iter()
+            .find(|(original, _)| UnverifiedDartHandle::identity_eq(*original, item))
+            .map(|&(_, copied)| copied);
+        let copied = match copied {
+            Some(copied) => copied,
+            None => deep_clone_list(item, copies)?,
+        };
+        copy.list_set_at(copied, idx)?;
+    }
+    Ok(copy)
+}
+
 impl<T> Deref for List<T> {
     type Target = UnverifiedDartHandle;
     fn deref(&self) -> &UnverifiedDartHandle {
@@ -134,9 +882,9 @@ pub trait ListLike<T> {
         };
         let end = slice.end_bound();
         let end = match end {
-            std::ops::Bound::Unbounded => self.len() - 1,
-            std::ops::Bound::Included(x) => *x,
-            std::ops::Bound::Excluded(x) => *x - 1,
+            std::ops::Bound::Unbounded => self.len(),
+            std::ops::Bound::Included(x) => *x + 1,
+            std::ops::Bound::Excluded(x) => *x,
         };
         let len = end - start;
         ListView::new(start, len, self)
@@ -153,14 +901,31 @@ pub trait ListLike<T> {
         };
         let end = slice.end_bound();
         let end = match end {
-            std::ops::Bound::Unbounded => self.len() - 1,
-            std::ops::Bound::Included(x) => *x,
-            std::ops::Bound::Excluded(x) => *x - 1,
+            std::ops::Bound::Unbounded => self.len(),
+            std::ops::Bound::Included(x) => *x + 1,
+            std::ops::Bound::Excluded(x) => *x,
         };
         let len = end - start;
         ListViewMut::new(start, len, self)
     }
 
+    ///
+    /// Sets `items.len()` consecutive elements starting at `start`.
+    ///
+    /// By default this calls [`set_at`](ListLike::set_at) for each
+    /// element, but lists which can write several elements at once,
+    /// such as typed data, override this.
+    ///
+    fn set_range_from(&mut self, start: usize, items: &[T]) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        for (idx, item) in items.iter().enumerate() {
+            self.set_at(start + idx, item.clone())?;
+        }
+        Ok(())
+    }
+
     fn len(&self) -> usize;
 }
 
@@ -386,16 +1151,33 @@ impl<'a, T: Clone, L: ListLike<T> + ?Sized> IndexMut<usize> for ListViewMut<'a,
 
 impl<'a, T: Clone, L: ListLike<T> + ?Sized> Drop for ListViewMut<'a, T, L> {
     fn drop(&mut self) {
+        // Consecutive modified items are written back together, so that
+        // lists overriding `set_range_from` can do so in bulk.
+        let mut run = Vec::new();
+        let mut run_start = 0;
         for (idx, i) in self.cached_items.iter().enumerate() {
             let i = i.get();
             unsafe {
                 let item = &*i;
                 match item {
-                    Item::None | Item::Read(_) => {}
-                    Item::PossiblyModified(x) => dart_unwrap!(self.list.set_at(idx + self.start, x.clone())),
+                    Item::None | Item::Read(_) => {
+                        if !run.is_empty() {
+                            dart_unwrap!(self.list.set_range_from(run_start + self.start, &run));
+                            run.clear();
+                        }
+                    }
+                    Item::PossiblyModified(x) => {
+                        if run.is_empty() {
+                            run_start = idx;
+                        }
+                        run.push(x.clone());
+                    }
                 }
             }
         }
+        if !run.is_empty() {
+            dart_unwrap!(self.list.set_range_from(run_start + self.start, &run));
+        }
     }
 }
 
@@ -424,10 +1206,49 @@ macro_rules! typed_data_impl {
                     handle.map(|x| <$out>::from_handle(x).ok().unwrap())
                 }
 
+                fn set_range_from(&mut self, start: usize, items: &[$out]) -> Result<(), Error> {
+                    let values = items.iter().map(|x| x.value() as $this).collect::<Vec<$this>>();
+                    if self.handle.is_typed_data() && start + values.len() <= self.length() {
+                        if std::mem::size_of::<$this>() == 1
+                            && self.handle.typed_data_get_type() == <$this as TypedData>::TYPE
+                        {
+                            let bytes = unsafe {
+                                std::slice::from_raw_parts(values.as_ptr() as *const u8, values.len())
+                            };
+                            return self.handle.list_set_as_bytes(start, bytes);
+                        }
+                        if let Some(mut data) = TypedDataGuard::<$this>::acquire(&self.handle)? {
+                            data[start..start + values.len()].copy_from_slice(&values);
+                            return Ok(());
+                        }
+                    }
+                    for (idx, item) in items.iter().enumerate() {
+                        self.set_at(start + idx, item.clone())?;
+                    }
+                    Ok(())
+                }
+
                 fn len(&self) -> usize {
                     self.length()
                 }
             }
+
+            impl List<$this> {
+                ///
+                /// Copies the elements of this list into a boxed slice.
+                /// Lists backed by typed data of the element type are
+                /// copied in one go through [`as_typed_data`](List::as_typed_data),
+                /// while any other list is read element by element.
+                ///
+                pub fn to_boxed_slice(&self) -> Box<[$this]> {
+                    if let Some(data) = self.as_typed_data() {
+                        return Box::from(&*data);
+                    }
+                    (0..self.length())
+                        .map(|idx| dart_unwrap!(self.get_at(idx)).value() as $this)
+                        .collect()
+                }
+            }
         )*
     }
 }
@@ -0,0 +1,155 @@
+use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
+use crate::dart_types::list::List;
+use crate::dart_types::DartType;
+use crate::dart_unwrap;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::thread::LocalKey;
+
+///
+/// A Dart [`Map`](https://api.dart.dev/stable/2.7.1/dart-core/Map-class.html)
+/// with keys of type `K` and values of type `V`.
+///
+/// As with [`List`], the types of the keys and values are not checked
+/// upon creation, but values are checked when read.
+///
+#[derive(Copy, Clone)]
+pub struct Map<K, V> {
+    _phantom: PhantomData<*mut (K, V)>,
+    handle: UnverifiedDartHandle,
+}
+
+impl<K: DartType, V: DartType> Map<K, V> {
+    ///
+    /// Creates a new, empty map whose type arguments are the types of
+    /// `K` and `V`, as [`List::new`] does.
+    ///
+    /// The cached types of some wrappers are those of their instances,
+    /// such as `_Smi` for an [`Integer`](crate::dart_types::integer::Integer),
+    /// which reject other instances of the same Dart type. Use
+    /// [`new_of`](Map::new_of) to choose the type arguments instead.
+    ///
+    pub fn new() -> Result<Self, Error> {
+        K::THIS.with(|k| V::THIS.with(|v| Self::new_with_args(&mut [*k, *v])))
+    }
+
+    ///
+    /// Creates a new, empty map with `key_type` and `value_type` as its
+    /// type arguments, such that `Map::new_of(String, int)` creates a
+    /// `Map<String, int>`.
+    ///
+    pub fn new_of(
+        key_type: UnverifiedDartHandle,
+        value_type: UnverifiedDartHandle,
+    ) -> Result<Self, Error> {
+        Self::new_with_args(&mut [key_type, value_type])
+    }
+
+    fn new_with_args(type_args: &mut [UnverifiedDartHandle]) -> Result<Self, Error> {
+        let handle = map_type(type_args)?.new_of_type_self(None, &mut [])?;
+        Ok(Self {
+            handle,
+            _phantom: PhantomData,
+        })
+    }
+
+    ///
+    /// Gets the value associated with `key`, or `None` should there be
+    /// none. Since this uses [`Dart_MapGetAt`](dart_sys::Dart_MapGetAt),
+    /// a key associated with `null` is also reported as `None`.
+    ///
+    /// Returns an error should the value not be of type `V`.
+    ///
+    pub fn get(&self, key: K) -> Result<Option<V>, Error> {
+        match self.handle.map_get_at(key.safe_handle())? {
+            Some(x) => V::from_handle(x)
+                .map(Some)
+                .map_err(|_| Error::new_api("Map value is not of the expected type").unwrap()),
+            None => Ok(None),
+        }
+    }
+
+    pub fn contains_key(&self, key: K) -> Result<bool, Error> {
+        self.handle.map_contains_key(key.safe_handle())?.get_bool()
+    }
+
+    ///
+    /// Associates `value` with `key`, replacing any previous value,
+    /// using Dart's `[]=` operator.
+    ///
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), Error> {
+        self.handle
+            .op_idx_assign(key.safe_handle(), value.safe_handle())
+    }
+
+    ///
+    /// Gets the keys of the map as a list, in the map's iteration
+    /// order.
+    ///
+    pub fn keys(&self) -> Result<List<K>, Error> {
+        let keys = self.handle.map_keys()?;
+        Ok(List::from_handle(keys).ok().unwrap())
+    }
+}
+
+impl<K, V> Map<K, V> {
+    pub fn len(&self) -> usize {
+        let length = self
+            .handle
+            .get_field(UnverifiedDartHandle::string_from_str("length"));
+        dart_unwrap!(dart_unwrap!(length).get_i64()) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+///
+/// Gets the type of `Map` with `type_args` as its type arguments,
+/// or the raw `Map` type should there be none.
+///
+fn map_type(type_args: &mut [UnverifiedDartHandle]) -> Result<UnverifiedDartHandle, Error> {
+    let library =
+        UnverifiedDartHandle::lookup_library(UnverifiedDartHandle::string_from_str("dart:core"))?;
+    UnverifiedDartHandle::make_type_from_decl(
+        library,
+        UnverifiedDartHandle::string_from_str("Map"),
+        type_args,
+    )
+}
+
+thread_local! {
+    #[allow(non_upper_case_globals)]
+    pub static MapType: UnverifiedDartHandle = map_type(&mut []).ok().unwrap();
+}
+
+unsafe impl<K: 'static, V: 'static> DartHandle for Map<K, V> {
+    fn handle(&self) -> dart_sys::Dart_Handle {
+        self.handle.handle()
+    }
+    fn safe_handle(&self) -> UnverifiedDartHandle {
+        self.handle
+    }
+    fn from_handle(handle: UnverifiedDartHandle) -> Result<Self, UnverifiedDartHandle> {
+        if handle.is_map() {
+            Ok(Self {
+                handle,
+                _phantom: PhantomData,
+            })
+        } else {
+            Err(handle)
+        }
+    }
+}
+
+impl<K, V> Deref for Map<K, V> {
+    type Target = UnverifiedDartHandle;
+    fn deref(&self) -> &UnverifiedDartHandle {
+        &self.handle
+    }
+}
+
+impl<K: DartType, V: DartType> DartType for Map<K, V> {
+    const THIS: &'static LocalKey<UnverifiedDartHandle> = &MapType;
+}
@@ -16,11 +16,16 @@ use crate::dart_handle::{DartHandle, UnverifiedDartHandle};
 use std::thread::LocalKey;
 
 pub mod boolean;
+pub mod class;
+pub mod closure;
 pub mod d_string;
 pub mod double;
 pub mod dynamic;
+pub mod future;
 pub mod integer;
 pub mod list;
+pub mod map;
+pub mod set;
 
 ///
 /// Trait which describes types of objects in terms of
@@ -0,0 +1,168 @@
+use crate::dart_handle::{DartHandle, Error, UnverifiedDartHandle};
+use crate::dart_types::DartType;
+use crate::dart_unwrap;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::thread::LocalKey;
+
+///
+/// A Dart [`Set`](https://api.dart.dev/stable/2.7.1/dart-core/Set-class.html)
+/// with elements of type `T`.
+///
+/// As with [`List`](crate::dart_types::list::List), the type of the
+/// elements is not checked upon creation.
+///
+#[derive(Copy, Clone)]
+pub struct Set<T> {
+    _phantom: PhantomData<*mut T>,
+    handle: UnverifiedDartHandle,
+}
+
+impl<T: DartType> Set<T> {
+    ///
+    /// Creates a new, empty set whose type argument is the type of
+    /// `T`, as [`List::new`](crate::dart_types::list::List::new) does.
+    ///
+    /// The cached types of some wrappers are those of their instances,
+    /// such as `_Smi` for an [`Integer`](crate::dart_types::integer::Integer),
+    /// which reject other instances of the same Dart type. Use
+    /// [`new_of`](Set::new_of) to choose the type argument instead.
+    ///
+    pub fn new() -> Result<Self, Error> {
+        T::THIS.with(|ty| Self::new_with_args(&mut [*ty]))
+    }
+
+    ///
+    /// Creates a new, empty set with `element_type` as its type
+    /// argument, such that `Set::new_of(int)` creates a `Set<int>`.
+    ///
+    pub fn new_of(element_type: UnverifiedDartHandle) -> Result<Self, Error> {
+        Self::new_with_args(&mut [element_type])
+    }
+
+    fn new_with_args(type_args: &mut [UnverifiedDartHandle]) -> Result<Self, Error> {
+        let handle = set_type(type_args)?.new_of_type_self(None, &mut [])?;
+        Ok(Self {
+            handle,
+            _phantom: PhantomData,
+        })
+    }
+
+    ///
+    /// Adds `item` to the set, returning whether it wasn't already
+    /// present.
+    ///
+    pub fn add(&mut self, item: T) -> Result<bool, Error> {
+        self.invoke_bool("add", item.safe_handle())
+    }
+
+    ///
+    /// Removes `item` from the set, returning whether it was present.
+    ///
+    pub fn remove(&mut self, item: &T) -> Result<bool, Error> {
+        self.invoke_bool("remove", item.safe_handle())
+    }
+
+    pub fn contains(&self, item: &T) -> Result<bool, Error> {
+        self.invoke_bool("contains", item.safe_handle())
+    }
+
+    ///
+    /// Creates a new set containing the elements of both this set and
+    /// `other`, which must be a Dart `Set`.
+    ///
+    pub fn union(&self, other: impl DartHandle) -> Result<Set<T>, Error> {
+        self.invoke_set("union", other.safe_handle())
+    }
+
+    ///
+    /// Creates a new set containing the elements of this set which are
+    /// also in `other`.
+    ///
+    pub fn intersection(&self, other: impl DartHandle) -> Result<Set<T>, Error> {
+        self.invoke_set("intersection", other.safe_handle())
+    }
+
+    ///
+    /// Creates a new set containing the elements of this set which are
+    /// not in `other`.
+    ///
+    pub fn difference(&self, other: impl DartHandle) -> Result<Set<T>, Error> {
+        self.invoke_set("difference", other.safe_handle())
+    }
+
+    fn invoke_bool(&self, name: &str, arg: UnverifiedDartHandle) -> Result<bool, Error> {
+        self.handle
+            .invoke(UnverifiedDartHandle::string_from_str(name), &mut [arg])?
+            .get_bool()
+    }
+
+    fn invoke_set(&self, name: &str, arg: UnverifiedDartHandle) -> Result<Set<T>, Error> {
+        let handle = self
+            .handle
+            .invoke(UnverifiedDartHandle::string_from_str(name), &mut [arg])?;
+        Ok(Self {
+            handle,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<T> Set<T> {
+    pub fn length(&self) -> usize {
+        let length = self
+            .handle
+            .get_field(UnverifiedDartHandle::string_from_str("length"));
+        dart_unwrap!(dart_unwrap!(length).get_i64()) as usize
+    }
+}
+
+///
+/// Gets the type of `Set` with `type_args` as its type arguments,
+/// or the raw `Set` type should there be none.
+///
+fn set_type(type_args: &mut [UnverifiedDartHandle]) -> Result<UnverifiedDartHandle, Error> {
+    let library =
+        UnverifiedDartHandle::lookup_library(UnverifiedDartHandle::string_from_str("dart:core"))?;
+    UnverifiedDartHandle::make_type_from_decl(
+        library,
+        UnverifiedDartHandle::string_from_str("Set"),
+        type_args,
+    )
+}
+
+thread_local! {
+    #[allow(non_upper_case_globals)]
+    pub static SetType: UnverifiedDartHandle = set_type(&mut []).ok().unwrap();
+}
+
+unsafe impl<T: 'static> DartHandle for Set<T> {
+    fn handle(&self) -> dart_sys::Dart_Handle {
+        self.handle.handle()
+    }
+    fn safe_handle(&self) -> UnverifiedDartHandle {
+        self.handle
+    }
+    fn from_handle(handle: UnverifiedDartHandle) -> Result<Self, UnverifiedDartHandle> {
+        let is_set = SetType.with(|ty| handle.instanceof(*ty)).unwrap_or(false);
+        if is_set {
+            Ok(Self {
+                handle,
+                _phantom: PhantomData,
+            })
+        } else {
+            Err(handle)
+        }
+    }
+}
+
+impl<T> Deref for Set<T> {
+    type Target = UnverifiedDartHandle;
+    fn deref(&self) -> &UnverifiedDartHandle {
+        &self.handle
+    }
+}
+
+impl<T: DartType> DartType for Set<T> {
+    const THIS: &'static LocalKey<UnverifiedDartHandle> = &SetType;
+}
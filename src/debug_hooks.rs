@@ -0,0 +1,71 @@
+//!
+//! Hooks for pausing the current isolate, useful when diagnosing
+//! startup and load-order issues with a debugger attached.
+//!
+//! This module is only available with the `debug-hooks` feature.
+//!
+//! # Preconditions
+//! All of these functions operate on the current isolate, and as
+//! such must only be called from within a native function call or
+//! while an isolate is otherwise entered.
+//!
+//! Pausing is a feature of the VM service, so these are effectively
+//! no-ops when running in AOT mode or without the service enabled.
+//!
+
+use dart_sys as ffi;
+
+///
+/// Sets whether the current isolate should pause before running
+/// its main function.
+///
+/// See [`Dart_SetShouldPauseOnStart`](::dart_sys::Dart_SetShouldPauseOnStart).
+///
+pub fn pause_isolate_on_start(should_pause: bool) {
+    unsafe { ffi::Dart_SetShouldPauseOnStart(should_pause) }
+}
+
+pub fn should_pause_on_start() -> bool {
+    unsafe { ffi::Dart_ShouldPauseOnStart() }
+}
+
+pub fn is_paused_on_start() -> bool {
+    unsafe { ffi::Dart_IsPausedOnStart() }
+}
+
+///
+/// Marks the current isolate as paused (or resumed) on start.
+/// This is what the debugger observes.
+///
+/// See [`Dart_SetPausedOnStart`](::dart_sys::Dart_SetPausedOnStart).
+///
+pub fn set_paused_on_start(paused: bool) {
+    unsafe { ffi::Dart_SetPausedOnStart(paused) }
+}
+
+///
+/// Sets whether the current isolate should pause once it has
+/// finished running.
+///
+/// See [`Dart_SetShouldPauseOnExit`](::dart_sys::Dart_SetShouldPauseOnExit).
+///
+pub fn pause_isolate_on_exit(should_pause: bool) {
+    unsafe { ffi::Dart_SetShouldPauseOnExit(should_pause) }
+}
+
+pub fn should_pause_on_exit() -> bool {
+    unsafe { ffi::Dart_ShouldPauseOnExit() }
+}
+
+pub fn is_paused_on_exit() -> bool {
+    unsafe { ffi::Dart_IsPausedOnExit() }
+}
+
+///
+/// Marks the current isolate as paused (or resumed) on exit.
+///
+/// See [`Dart_SetPausedOnExit`](::dart_sys::Dart_SetPausedOnExit).
+///
+pub fn set_paused_on_exit(paused: bool) {
+    unsafe { ffi::Dart_SetPausedOnExit(paused) }
+}
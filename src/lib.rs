@@ -1,20 +1,48 @@
+//!
+//! Idiomatic bindings to the dart native extensions api.
+//!
+//! # Features
+//! - **`registration`** (enabled by default) provides the global
+//!   function register and the `export_dart_functions` and
+//!   `create_init_function` macros. Embedders which set their own
+//!   native resolver may disable it to use the handle layer alone,
+//!   without pulling in `lazy_static` and `mashup`.
+//! - **`debug-hooks`** provides the `debug_hooks` module, as well as
+//!   counters for verifying that scopes are balanced and that posted
+//!   messages are freed.
+//! - **`metrics`** provides the `metrics` module.
+//!
+
+#[cfg(feature = "registration")]
 use crate::dart_handle::{DartHandle, UnverifiedDartHandle};
 use dart_sys as ffi;
+#[cfg(feature = "registration")]
 use lazy_static::lazy_static;
-use std::collections::HashMap;
-use std::ffi::{CStr, CString};
+#[cfg(feature = "registration")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "registration")]
+use std::ffi::CStr;
+use std::ffi::CString;
+#[cfg(feature = "registration")]
 use std::os::raw::c_char;
 use std::panic::{catch_unwind, UnwindSafe};
-use std::sync::RwLock;
+#[cfg(feature = "registration")]
+use std::sync::{RwLock, RwLockReadGuard};
 
 pub mod dart_cobject;
 pub mod dart_handle;
 pub mod dart_native_arguments;
 pub mod dart_types;
+#[cfg(feature = "debug-hooks")]
+pub mod debug_hooks;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod prelude;
 
+#[cfg(feature = "registration")]
 extern crate mashup;
 
+#[cfg(feature = "registration")]
 lazy_static! {
     ///
     /// The global register for functions.
@@ -31,25 +59,45 @@ pub type NativeFunction = unsafe extern "C" fn(arguments: ffi::Dart_NativeArgume
 ///
 /// This is created and loaded at startup in the `init` function.
 ///
+#[cfg(feature = "registration")]
 #[derive(Default)]
-#[doc(hidden)]
 pub struct FunctionRegister {
     ///
-    /// The name -> function connection.
+    /// The name -> function connection. Functions registered with an
+    /// argument count only resolve for that argument count.
     ///
-    functions: HashMap<&'static CStr, NativeFunction>,
+    functions: HashMap<&'static CStr, Vec<(Option<usize>, NativeFunction)>>,
     ///
     /// The function -> name connection.
     ///
     function_names: HashMap<NativeFunction, &'static CStr>,
+    ///
+    /// The functions for which the VM should not set up a scope.
+    ///
+    no_scope: HashSet<NativeFunction>,
 }
 
+#[cfg(feature = "registration")]
 impl FunctionRegister {
     ///
     /// Adds a function into the register. Leaks the name and puts
     /// it into both `HashMap`s.
     ///
     pub fn add_function(&mut self, function: NativeFunction, name: &str) {
+        self.add_function_with_argc(function, name, None)
+    }
+
+    ///
+    /// Adds a function into the register, which is only resolved when
+    /// called with `argc` arguments should it be `Some`. This allows
+    /// several functions of different arities to share a name.
+    ///
+    pub fn add_function_with_argc(
+        &mut self,
+        function: NativeFunction,
+        name: &str,
+        argc: Option<usize>,
+    ) {
         //Convert name to cstring
         let name = CString::new(name).unwrap();
         //SAFETY:
@@ -64,19 +112,78 @@ impl FunctionRegister {
             let leaked = Box::leak::<'static>(boxed_slice);
             CStr::from_bytes_with_nul_unchecked(&*leaked)
         };
-        self.functions.insert(name, function);
+        let functions = self.functions.entry(name).or_insert_with(Vec::new);
+        functions.retain(|(x, _)| *x != argc);
+        functions.push((argc, function));
         self.function_names.insert(function, name);
     }
 
     ///
-    /// Gets a function given a name.
+    /// Sets whether the VM should set up a scope whenever `function`
+    /// is called, which it does by default. Functions without a scope
+    /// must not create any handles, such as those only reading scalar
+    /// arguments and setting a scalar return value.
+    ///
+    pub fn set_auto_scope(&mut self, function: NativeFunction, auto_scope: bool) {
+        if auto_scope {
+            self.no_scope.remove(&function);
+        } else {
+            self.no_scope.insert(function);
+        }
+    }
+
+    ///
+    /// Checks whether the VM should set up a scope whenever `function`
+    /// is called. See [`set_auto_scope`](FunctionRegister::set_auto_scope).
+    ///
+    pub fn auto_scope(&self, function: NativeFunction) -> bool {
+        !self.no_scope.contains(&function)
+    }
+
+    ///
+    /// Gets a function given a name and the number of arguments it is
+    /// called with. A function registered for exactly `argc` arguments
+    /// is preferred over one registered without an argument count.
     ///
     /// # SAFETY:
     ///  `name` must be a valid pointer to a nul-terminated C-string.
     ///
-    pub unsafe fn get_function(&self, name: *const c_char) -> ffi::Dart_NativeFunction {
+    pub unsafe fn get_function(
+        &self,
+        name: *const c_char,
+        argc: usize,
+    ) -> ffi::Dart_NativeFunction {
         let name = CStr::from_ptr::<'static>(name);
-        self.functions.get(name).cloned()
+        let functions = self.functions.get(name)?;
+        functions
+            .iter()
+            .find(|(x, _)| *x == Some(argc))
+            .or_else(|| functions.iter().find(|(x, _)| x.is_none()))
+            .map(|(_, x)| *x)
+    }
+
+    ///
+    /// Lists the names of all registered functions, in sorted order.
+    /// This is useful for logging which functions an extension exports.
+    ///
+    pub fn registered_names(&self) -> Vec<String> {
+        let mut names = self
+            .functions
+            .keys()
+            .map(|x| x.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    ///
+    /// Checks if a function has been registered under `name`.
+    ///
+    pub fn contains(&self, name: &str) -> bool {
+        match CString::new(name) {
+            Ok(name) => self.functions.contains_key(name.as_c_str()),
+            Err(_) => false,
+        }
     }
 
     ///
@@ -92,10 +199,21 @@ impl FunctionRegister {
     }
 }
 
+///
+/// Gets read access to the global `FunctionRegister`, for inspecting
+/// which functions have been registered. This is only filled in once
+/// the `init` function created by `create_init_function` has run.
+///
+#[cfg(feature = "registration")]
+pub fn function_register() -> RwLockReadGuard<'static, FunctionRegister> {
+    REGISTER.read().unwrap()
+}
+
 ///
 /// Registers a set of functions given mutable access to the global
 /// `FunctionRegister`. Generate this using `export_dart_functions`.
 ///
+#[cfg(feature = "registration")]
 #[derive(Copy, Clone)]
 #[doc(hidden)]
 pub struct Registerer {
@@ -120,13 +238,22 @@ pub struct Registerer {
 ///   global register. These are created using `export_dart_functions`,
 ///   and are passed into the `create_init_function`.
 ///
+/// - **`setup`** is run once the register has been filled in, but before
+///   the resolvers are set. Should it panic, the panic is returned as an
+///   error.
+///
 /// # Safety
 ///
 /// `parent_library` must be a valid `Dart_Handle`. Not doing so will cause
 /// the VM to invoke UB.
 ///
+#[cfg(feature = "registration")]
 #[doc(hidden)]
-pub unsafe fn init(parent_library: ffi::Dart_Handle, registers: &[Registerer]) -> ffi::Dart_Handle {
+pub unsafe fn init(
+    parent_library: ffi::Dart_Handle,
+    registers: &[Registerer],
+    setup: Option<fn()>,
+) -> ffi::Dart_Handle {
     let parent_library = UnverifiedDartHandle::new(parent_library).get_error();
     if parent_library.is_err() {
         return parent_library.handle();
@@ -138,6 +265,13 @@ pub unsafe fn init(parent_library: ffi::Dart_Handle, registers: &[Registerer]) -
     }
     drop(lock);
 
+    if let Some(setup) = setup {
+        if let Err(e) = catch_unwind(setup) {
+            let msg = format!("Setup panicked: {}", panic_message(&*e));
+            return crate::dart_handle::Error::new_api_sanitized(&msg).handle();
+        }
+    }
+
     //Sets the appropriate resolvers for the library.
     let result_code = ffi::Dart_SetNativeResolver(
         parent_library.handle(),  //Library
@@ -158,10 +292,12 @@ pub unsafe fn init(parent_library: ffi::Dart_Handle, registers: &[Registerer]) -
 ///
 /// - **`name`** is a Dart String with the name of the function.
 ///
-/// - **`_argc`** is the number of parameters in the function. Currently unused.
+/// - **`argc`** is the number of parameters the function is called with.
+///   Functions exported with an argument count only resolve for that count.
 ///
 /// - **`auto_scope_setup`** is a flag which signals whether the VM should setup
-///   a scope for this function. This will be set to true by default.
+///   a scope for this function. This will be set to true unless the function
+///   was exported `as no_scope`.
 ///
 /// # Safety
 ///
@@ -171,11 +307,11 @@ pub unsafe fn init(parent_library: ffi::Dart_Handle, registers: &[Registerer]) -
 /// does not need to be a valid `bool` since its destructor is not run and
 /// LLVM cannot claim UB.
 ///
+#[cfg(feature = "registration")]
 #[allow(dead_code)] //Usage of this function is declared in external crates.
-// TODO: Implement argument counting
 unsafe extern "C" fn resolve_name(
     name: ffi::Dart_Handle,
-    _argc: std::os::raw::c_int,
+    argc: std::os::raw::c_int,
     auto_scope_setup: *mut bool,
 ) -> ffi::Dart_NativeFunction {
     let name = UnverifiedDartHandle::new(name).get_error().ok()?;
@@ -184,19 +320,22 @@ unsafe extern "C" fn resolve_name(
         return None;
     }
 
-    std::ptr::write(auto_scope_setup, true);
-
     // Go directly through the UnverifiedDartHandle interface since
     // we actually need a `CString`, and `DString` only gives us
     // regular `String`s.
     let cname = dart_unwrap!(name.to_string());
 
-    REGISTER.read().unwrap().get_function(cname.as_ptr())
+    let register = REGISTER.read().unwrap();
+    let function = register.get_function(cname.as_ptr(), argc as usize);
+    let auto_scope = function.map_or(true, |x| register.auto_scope(x));
+    std::ptr::write(auto_scope_setup, auto_scope);
+    function
 }
 
 ///
 /// Finds a function's name given its pointer in the global register.
 ///
+#[cfg(feature = "registration")]
 extern "C" fn resolve_function(function: ffi::Dart_NativeFunction) -> *const u8 {
     let name = REGISTER.read().unwrap().get_name_from_function(function);
     if let Some(x) = name {
@@ -220,6 +359,10 @@ extern "C" fn resolve_function(function: ffi::Dart_NativeFunction) -> *const u8
 /// - **`value`** is the parameters with which `f` is intended to be
 ///   called. This function will wrap this and send it to `f`.
 ///
+/// - **`name`** is the name `f` was registered under, which is
+///   reported along with the duration of the call with the `metrics`
+///   feature.
+///
 /// # Safety
 ///
 /// This function requires `value` to be a valid pointer to function
@@ -229,25 +372,101 @@ extern "C" fn resolve_function(function: ffi::Dart_NativeFunction) -> *const u8
 pub unsafe fn catch_panic_hook(
     f: impl FnOnce(crate::dart_native_arguments::NativeArguments) + UnwindSafe,
     value: ffi::Dart_NativeArguments,
+    name: &str,
 ) {
+    #[cfg(feature = "metrics")]
+    let start = std::time::Instant::now();
     let result = catch_unwind(move || {
         f(crate::dart_native_arguments::NativeArguments::new(value))
     });
+    #[cfg(feature = "metrics")]
+    crate::metrics::record_call(name, start.elapsed());
+    #[cfg(not(feature = "metrics"))]
+    let _ = name;
     if let Err(e) = result {
-        let msg;
-        match e.downcast_ref::<String>() {
-            Some(x) => msg = &**x,
-            None => match e.downcast::<&str>() {
-                Ok(x) => msg = *x,
-                Err(_e) => msg = "Panic of unknown nature in Rust code!",
-            },
-        }
+        let error = crate::dart_handle::Error::new_api(panic_message(&*e)).unwrap();
+        error.propagate_error();
+    }
+}
 
-        let error = crate::dart_handle::Error::new_api(msg).unwrap();
+///
+/// Runs a synchronous function for which the VM has not set up a
+/// scope, and protects against unwinding into C stack frames.
+///
+/// This is the same as [`catch_panic_hook`], except that a scope is
+/// entered to create the error, should `f` panic. `f` itself must not
+/// create any handles.
+///
+/// # Safety
+///
+/// The same as [`catch_panic_hook`].
+///
+#[doc(hidden)]
+pub unsafe fn catch_panic_hook_no_scope(
+    f: impl FnOnce(crate::dart_native_arguments::NativeArguments) + UnwindSafe,
+    value: ffi::Dart_NativeArguments,
+    name: &str,
+) {
+    #[cfg(feature = "metrics")]
+    let start = std::time::Instant::now();
+    let result = catch_unwind(move || {
+        f(crate::dart_native_arguments::NativeArguments::new(value))
+    });
+    #[cfg(feature = "metrics")]
+    crate::metrics::record_call(name, start.elapsed());
+    #[cfg(not(feature = "metrics"))]
+    let _ = name;
+    if let Err(e) = result {
+        // The scope is left behind, since propagating the error
+        // never returns. The VM cleans up scopes once it unwinds.
+        crate::dart_handle::enter_scope();
+        let error = crate::dart_handle::Error::new_api_sanitized(panic_message(&*e));
         error.propagate_error();
     }
 }
 
+///
+/// Gets the message a panic was started with.
+///
+fn panic_message(e: &(dyn std::any::Any + Send)) -> &str {
+    match e.downcast_ref::<String>() {
+        Some(x) => &**x,
+        None => match e.downcast_ref::<&str>() {
+            Some(x) => *x,
+            None => "Panic of unknown nature in Rust code!",
+        },
+    }
+}
+
+///
+/// Runs a synchronous function within its own scope, and protects
+/// against unwinding into C stack frames.
+///
+/// This is the same as [`catch_panic_hook`], except that it doesn't
+/// rely on the VM having set up a scope for the call. The scope is
+/// exited once `f` returns or panics, so handles it creates must not
+/// be returned using anything but `set_return`.
+///
+/// # Safety
+///
+/// The same as [`catch_panic_hook`].
+///
+#[doc(hidden)]
+pub unsafe fn catch_panic_hook_scoped(
+    f: impl FnOnce(crate::dart_native_arguments::NativeArguments) + UnwindSafe,
+    value: ffi::Dart_NativeArguments,
+    name: &str,
+) {
+    catch_panic_hook(
+        move |x| {
+            let _scope = crate::dart_handle::DartScope::enter();
+            f(x)
+        },
+        value,
+        name,
+    );
+}
+
 ///
 /// Creates and returns a `SendPort` for an asynchronous function.
 ///
@@ -293,18 +512,17 @@ pub unsafe fn catch_panic_hook_async(
                 crate::dart_handle::exit_scope();
                 panic!("Name is invalid: `{}`", e);
             });
-            let service_port =
-                crate::dart_handle::NativePort::new_native(name.clone(), f)
-                    .unwrap_or_else(|| {
+            let receive_port =
+                crate::dart_handle::ReceivePort::new(name.clone(), f)
+                    .unwrap_or_else(|e| {
                         crate::dart_handle::exit_scope();
-                        panic!("Name is invalid: `{:?}`", name);
+                        panic!("Could not create port `{:?}`: {:?}", name, e);
                     });
-            let (_, send_port_instance) =
-                crate::dart_handle::Port::new(service_port.port()).unwrap();
-            x.set_return(send_port_instance);
+            x.set_return(receive_port.send_port());
             crate::dart_handle::exit_scope();
         },
         value,
+        name,
     );
 }
 
@@ -317,18 +535,28 @@ pub unsafe fn catch_panic_hook_async(
 /// is that we should abort the process instead of returning an
 /// error.
 ///
+/// With the `metrics` feature, the time taken to handle each message
+/// is reported under `name`.
+///
 #[doc(hidden)]
 pub unsafe fn catch_async_panic(
     func: fn(crate::dart_cobject::CObject, crate::dart_handle::Port),
     port: ffi::Dart_Port,
     message: *mut ffi::Dart_CObject,
+    name: &str,
 ) {
+    #[cfg(feature = "metrics")]
+    let start = std::time::Instant::now();
     let result = catch_unwind(move ||
         func(
             crate::dart_cobject::CObject::from(*message),
             crate::dart_handle::Port::from_port(port).unwrap(),
         )
     );
+    #[cfg(feature = "metrics")]
+    crate::metrics::record_call(name, start.elapsed());
+    #[cfg(not(feature = "metrics"))]
+    let _ = name;
     // We can ignore the error message since it will already have been printed.
     if result.is_err() {
         eprintln!("Rust panicked in an unwind-unsafe way. Aborting the process.");
@@ -350,7 +578,7 @@ pub unsafe fn catch_async_panic(
 ///   }
 ///   fn my_async_function(message: CObject, _from_port: Port) {
 ///       if let CObject::SendPort(port) = message {
-///           let port = unsafe { Port::from_port(port.0.id) }.unwrap();
+///           let port = unsafe { SendPort::from_port(port.0.id) }.unwrap();
 ///           port.post_cobject(CObject::String(CString::new("Hello, Async World").unwrap()));
 ///       } else {
 ///           panic!("Didn't get a port to reply!");
@@ -366,7 +594,7 @@ pub unsafe fn catch_async_panic(
 ///   # }
 ///   # fn my_async_function(message: CObject, _from_port: Port) {
 ///   #     if let CObject::SendPort(port) = message {
-///   #         let port = unsafe { Port::from_port(port.0.id) }.unwrap();
+///   #         let port = unsafe { SendPort::from_port(port.0.id) }.unwrap();
 ///   #         port.post_cobject(CObject::String(CString::new("Hello, Async World").unwrap()));
 ///   #     } else {
 ///   #         panic!("Didn't get a port to reply!");
@@ -378,10 +606,22 @@ pub unsafe fn catch_async_panic(
 ///       ["function2service_port" -> my_async_function as async]
 ///   );
 ///   ```
-///
+/// - Synchronous functions may also be exported `as scoped`, which
+///   enters a new scope for the duration of the call instead of relying
+///   on the VM to have set one up:
+///   `["function1" -> my_function as scoped]`.
+/// - Synchronous functions which create no handles, such as those
+///   only reading scalar arguments and setting a scalar return value,
+///   may be exported `as no_scope` to skip the VM setting up a scope
+///   for each call: `["function1" -> my_function as no_scope]`.
+/// - Functions may be restricted to a number of arguments, so that
+///   several functions of different arities can share a name:
+///   `["function1" / 2 -> my_function]`.
+///
+#[cfg(feature = "registration")]
 #[macro_export]
 macro_rules! export_dart_functions {
-    ($export_name:ident: $([$name:literal -> $function:ident $(as $a_sync:tt)?]),*$(,)?) => {
+    ($export_name:ident: $([$name:literal $(/ $argc:literal)? -> $function:ident $(as $a_sync:tt)?]),*$(,)?) => {
         use mashup::*;
         #[allow(non_snake_case, non_upper_case_globals)]
         static $export_name: $crate::Registerer = $crate::Registerer {
@@ -399,9 +639,10 @@ macro_rules! export_dart_functions {
                         $function! {
                             // TODO: Implement some way to automatically convert arguments.
                             unsafe extern "C" fn "n"(x: ::dart_sys::Dart_NativeArguments) {
-                                export_dart_functions!(@$($a_sync as ("n_async", $name))?, $function, x);
+                                export_dart_functions!(@$($a_sync)? as ("n_async", $name), $function, x);
                             }
-                            register.add_function("n", $name);
+                            register.add_function_with_argc("n", $name, export_dart_functions!(@argc $($argc)?));
+                            register.set_auto_scope("n", export_dart_functions!(@auto_scope $($a_sync)?));
                         }
                     )*
                 }
@@ -409,13 +650,31 @@ macro_rules! export_dart_functions {
             }
         };
     };
-    (@, $func:ident, $args:ident) => {
-        $crate::catch_panic_hook($func, $args);
+    (@argc) => {
+        ::std::option::Option::None
+    };
+    (@argc $argc:literal) => {
+        ::std::option::Option::Some($argc)
+    };
+    (@auto_scope no_scope) => {
+        false
+    };
+    (@auto_scope $($a_sync:tt)?) => {
+        true
+    };
+    (@ as ($async_name:ident, $registered_name:literal), $func:ident, $args:ident) => {
+        $crate::catch_panic_hook($func, $args, $registered_name);
+    };
+    (@no_scope as ($async_name:ident, $registered_name:literal), $func:ident, $args:ident) => {
+        $crate::catch_panic_hook_no_scope($func, $args, $registered_name);
+    };
+    (@scoped as ($async_name:ident, $registered_name:literal), $func:ident, $args:ident) => {
+        $crate::catch_panic_hook_scoped($func, $args, $registered_name);
     };
     (@async as ($async_name:ident, $registered_name:literal), $func:ident, $args:ident) => {
         unsafe extern "C" fn $async_name(dest_port_id: ::dart_sys::Dart_Port, message: *mut ::dart_sys::Dart_CObject) {
             let _: fn(args: $crate::dart_cobject::CObject, reply: $crate::dart_handle::Port) = $func;
-            $crate::catch_async_panic($func, dest_port_id, message);
+            $crate::catch_async_panic($func, dest_port_id, message, $registered_name);
         }
         $crate::catch_panic_hook_async($async_name, $args, $registered_name)
     };
@@ -435,10 +694,27 @@ macro_rules! export_dart_functions {
 ///   # dart::export_dart_functions!(my_exports: );
 ///   dart::create_init_function!(library_name, [my_exports]);
 ///   ```
+/// - Optionally pass a `fn()` to run once the library is loaded, after
+///   the functions have been registered but before any are resolved.
+///   Should it panic, loading the library fails with the panic's message.
+///   ```
+///   # dart::export_dart_functions!(my_exports: );
+///   fn setup() {
+///       // Initialize logging, etc.
+///   }
+///   dart::create_init_function!(library_name, [my_exports], setup = setup);
+///   ```
 ///
+#[cfg(feature = "registration")]
 #[macro_export]
 macro_rules! create_init_function {
     ($crate_name:ident, [$($name:ident),*$(,)?]) => {
+        $crate::create_init_function!(@ $crate_name, [$($name),*], ::std::option::Option::None);
+    };
+    ($crate_name:ident, [$($name:ident),*$(,)?], setup = $setup:path) => {
+        $crate::create_init_function!(@ $crate_name, [$($name),*], ::std::option::Option::Some($setup));
+    };
+    (@ $crate_name:ident, [$($name:ident),*], $setup:expr) => {
         use mashup::*;
         ::mashup::mashup! {
             dart_rs_init_name["init"] = $crate_name _Init;
@@ -447,7 +723,7 @@ macro_rules! create_init_function {
             #[allow(non_snake_case, unused_variables)]
             #[no_mangle]
             unsafe extern "C" fn "init"(parent_library: ::dart_sys::Dart_Handle) -> ::dart_sys::Dart_Handle {
-                $crate::init(parent_library, &[$($name),*])
+                $crate::init(parent_library, &[$($name),*], $setup)
             }
         }
     };
@@ -485,3 +761,218 @@ macro_rules! dart_unwrap {
         }
     };
 }
+
+///
+/// Unwraps a result, returning the error from the enclosing function
+/// should it be present. This is the counterpart to [`dart_unwrap`],
+/// which instead propagates the error into the VM.
+///
+/// The enclosing function must return a `Result` whose error type
+/// implements `From<Error>`.
+///
+/// # Usage
+/// ```no_run
+/// # use dart::prelude::*;
+/// # use dart::dart_handle::{Error, UnverifiedDartHandle};
+/// fn third(list: List<Integer>) -> Result<UnverifiedDartHandle, Error> {
+///     let int = try_handle!(list.get_at(2));
+///     Ok(int.safe_handle())
+/// }
+/// ```
+///
+#[macro_export]
+macro_rules! try_handle {
+    ($x: expr) => {
+        match {
+            let y: Result<_, $crate::dart_handle::Error> = $x;
+            y
+        } {
+            ::std::result::Result::Ok(x) => x,
+            ::std::result::Result::Err(e) => {
+                return ::std::result::Result::Err(::std::convert::From::from(e));
+            }
+        }
+    };
+}
+
+///
+/// Declares a wrapper type for a Dart class, implementing
+/// [`DartHandle`](crate::dart_handle::DartHandle) and
+/// [`DartType`](crate::dart_types::DartType) for it.
+///
+/// The class is looked up by its library url and name the first time
+/// its type is needed on a thread, and is cached for that thread after
+/// that. [`from_handle`](crate::dart_handle::DartHandle::from_handle)
+/// accepts instances of the class and of its subclasses.
+///
+/// The generated type derefs to an
+/// [`UnverifiedDartHandle`](crate::dart_handle::UnverifiedDartHandle),
+/// and other methods may be added to it in a regular `impl` block.
+///
+/// # Usage
+/// ```no_run
+/// # use dart::prelude::*;
+/// dart_type! {
+///     /// A `Point` from `package:points/points.dart`.
+///     pub struct Point("package:points/points.dart", "Point");
+/// }
+///
+/// impl Point {
+///     pub fn x(&self) -> Dynamic {
+///         Dynamic::from(*self).get_field(DString::new("x")).unwrap()
+///     }
+/// }
+/// ```
+///
+#[macro_export]
+macro_rules! dart_type {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident($library:literal, $class:literal);) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone)]
+        $vis struct $name {
+            handle: $crate::dart_handle::UnverifiedDartHandle,
+        }
+
+        impl $crate::dart_types::DartType for $name {
+            const THIS: &'static ::std::thread::LocalKey<$crate::dart_handle::UnverifiedDartHandle> = {
+                ::std::thread_local! {
+                    static TYPE: $crate::dart_handle::UnverifiedDartHandle = {
+                        let class = $crate::dart_types::class::DartClass::new($library, $class);
+                        $crate::dart_handle::DartHandle::safe_handle(&class.unwrap())
+                    };
+                }
+                &TYPE
+            };
+        }
+
+        unsafe impl $crate::dart_handle::DartHandle for $name {
+            fn handle(&self) -> ::dart_sys::Dart_Handle {
+                $crate::dart_handle::DartHandle::handle(&self.handle)
+            }
+            fn safe_handle(&self) -> $crate::dart_handle::UnverifiedDartHandle {
+                self.handle
+            }
+            fn from_handle(
+                handle: $crate::dart_handle::UnverifiedDartHandle,
+            ) -> ::std::result::Result<Self, $crate::dart_handle::UnverifiedDartHandle> {
+                let is_instance = <Self as $crate::dart_types::DartType>::THIS
+                    .with(|ty| handle.instanceof(*ty))
+                    .unwrap_or(false);
+                if is_instance {
+                    Ok(Self { handle })
+                } else {
+                    Err(handle)
+                }
+            }
+        }
+
+        impl ::std::ops::Deref for $name {
+            type Target = $crate::dart_handle::UnverifiedDartHandle;
+            fn deref(&self) -> &Self::Target {
+                &self.handle
+            }
+        }
+    };
+}
+
+///
+/// Creates a [`DString`](crate::dart_types::d_string::DString) using
+/// the same syntax as [`format!`].
+///
+/// # Usage
+/// ```no_run
+/// # use dart::prelude::*;
+/// # use dart::dart_native_arguments::NativeArguments;
+/// fn describe(arguments: NativeArguments) {
+///     let string = d_format!("{}+{}", 1, 2);
+///     arguments.set_return(*string);
+/// }
+/// ```
+///
+#[macro_export]
+macro_rules! d_format {
+    ($($arg:tt)*) => {
+        $crate::dart_types::d_string::DString::from_fmt(::std::format_args!($($arg)*))
+    };
+}
+
+#[cfg(all(test, feature = "registration"))]
+mod tests {
+    use super::{FunctionRegister, NativeFunction};
+    use dart_sys as ffi;
+    use std::ffi::CString;
+    use std::hint::black_box;
+
+    // The bodies differ so that the functions aren't merged.
+    unsafe extern "C" fn unary(_: ffi::Dart_NativeArguments) {
+        black_box(1);
+    }
+
+    unsafe extern "C" fn binary(_: ffi::Dart_NativeArguments) {
+        black_box(2);
+    }
+
+    unsafe extern "C" fn variadic(_: ffi::Dart_NativeArguments) {
+        black_box(3);
+    }
+
+    fn resolve(register: &FunctionRegister, name: &str, argc: usize) -> Option<usize> {
+        let name = CString::new(name).unwrap();
+        unsafe { register.get_function(name.as_ptr(), argc) }.map(|x| x as usize)
+    }
+
+    fn address(function: NativeFunction) -> Option<usize> {
+        Some(function as usize)
+    }
+
+    #[test]
+    fn resolves_by_argument_count() {
+        let mut register = FunctionRegister::default();
+        register.add_function_with_argc(unary, "f", Some(1));
+        register.add_function_with_argc(binary, "f", Some(2));
+        assert_eq!(resolve(&register, "f", 1), address(unary));
+        assert_eq!(resolve(&register, "f", 2), address(binary));
+        assert_eq!(resolve(&register, "f", 0), None);
+        assert_eq!(resolve(&register, "g", 1), None);
+    }
+
+    #[test]
+    fn falls_back_to_any_argument_count() {
+        let mut register = FunctionRegister::default();
+        register.add_function(variadic, "f");
+        register.add_function_with_argc(unary, "f", Some(1));
+        assert_eq!(resolve(&register, "f", 1), address(unary));
+        assert_eq!(resolve(&register, "f", 3), address(variadic));
+    }
+
+    #[test]
+    fn reregistering_replaces_the_same_argument_count() {
+        let mut register = FunctionRegister::default();
+        register.add_function_with_argc(unary, "f", Some(1));
+        register.add_function_with_argc(binary, "f", Some(1));
+        assert_eq!(resolve(&register, "f", 1), address(binary));
+    }
+
+    #[test]
+    fn lists_registered_names() {
+        let mut register = FunctionRegister::default();
+        register.add_function(unary, "b");
+        register.add_function(binary, "a");
+        register.add_function_with_argc(variadic, "b", Some(2));
+        assert_eq!(register.registered_names(), vec!["a", "b"]);
+        assert!(register.contains("a"));
+        assert!(!register.contains("c"));
+        assert!(!register.contains("a\0b"));
+    }
+
+    #[test]
+    fn auto_scope_is_set_per_function() {
+        let mut register = FunctionRegister::default();
+        assert!(register.auto_scope(unary));
+        register.set_auto_scope(unary, false);
+        assert!(!register.auto_scope(unary));
+        assert!(register.auto_scope(binary));
+        register.set_auto_scope(unary, true);
+        assert!(register.auto_scope(unary));
+    }
+}
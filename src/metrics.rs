@@ -0,0 +1,82 @@
+//!
+//! Hooks for timing native function calls, useful to find slow
+//! native functions without timing each of them by hand.
+//!
+//! This module is only available with the `metrics` feature.
+//!
+//! # Example
+//! ```
+//! use dart::metrics::set_call_hook;
+//!
+//! set_call_hook(Some(Box::new(|name, duration| {
+//!     eprintln!("`{}` took {:?}", name, duration);
+//! })));
+//! ```
+//!
+
+use lazy_static::lazy_static;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+///
+/// A function called with the registered name and duration of
+/// each native function call.
+///
+pub type CallHook = Box<dyn Fn(&str, Duration) + Send + Sync>;
+
+lazy_static! {
+    static ref CALL_HOOK: RwLock<Option<Arc<dyn Fn(&str, Duration) + Send + Sync>>> =
+        RwLock::new(None);
+}
+
+///
+/// Sets the hook called after each native function call, replacing
+/// any previous hook. Passing `None` removes the hook.
+///
+/// Synchronous functions report the duration of the entire call.
+/// Asynchronous functions report the call creating their port, as
+/// well as each message they handle afterwards.
+///
+/// The hook is not locked while it runs, so it may itself set a new
+/// hook, which is called from the next native function call onwards.
+///
+pub fn set_call_hook(hook: Option<CallHook>) {
+    *CALL_HOOK.write().unwrap() = hook.map(Arc::from);
+}
+
+pub(crate) fn record_call(name: &str, duration: Duration) {
+    let hook = CALL_HOOK.read().unwrap().clone();
+    if let Some(hook) = hook {
+        hook(name, duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_call, set_call_hook};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    // The hook is global, so everything touching it is in one test.
+    #[test]
+    fn hook_is_called_without_being_locked() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        set_call_hook(Some(Box::new(move |name, duration| {
+            recorded.lock().unwrap().push((name.to_string(), duration));
+            if name == "replace" {
+                set_call_hook(None);
+            }
+        })));
+        record_call("first", Duration::from_millis(3));
+        record_call("replace", Duration::from_millis(5));
+        record_call("ignored", Duration::from_millis(7));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                ("first".to_string(), Duration::from_millis(3)),
+                ("replace".to_string(), Duration::from_millis(5)),
+            ]
+        );
+    }
+}
@@ -1,7 +1,20 @@
 pub use crate::dart_cobject::{CObject, TypedDataArray};
-pub use crate::dart_handle::{DartHandle, Port};
+pub use crate::dart_handle::{DartHandle, Port, SendPort};
 pub use crate::dart_native_arguments::NativeArguments;
 pub use crate::dart_types::{
-    boolean::Boolean, d_string::DString, double::Double, integer::Integer, dynamic::Dynamic, list::*, DartType,
+    boolean::Boolean,
+    class::DartClass,
+    closure::Closure,
+    d_string::DString,
+    double::{Double, OrdDouble},
+    dynamic::Dynamic,
+    future::Future,
+    integer::Integer,
+    list::*,
+    map::Map,
+    set::Set,
+    DartType,
 };
-pub use crate::{create_init_function, dart_unwrap, export_dart_functions};
+#[cfg(feature = "registration")]
+pub use crate::{create_init_function, export_dart_functions};
+pub use crate::{d_format, dart_type, dart_unwrap, try_handle};
@@ -0,0 +1,11 @@
+//!
+//! Checks misuses of handles which must not compile, such as sending
+//! them to another thread. The rest of the `Send`/`Sync` audit is
+//! asserted in the unit tests of `dart_handle`.
+//!
+
+#[test]
+fn compile_fail() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/compile_fail/*.rs");
+}
@@ -0,0 +1,6 @@
+use dart::prelude::*;
+
+fn main() {
+    let s = DString::new("sent");
+    std::thread::spawn(move || drop(s));
+}
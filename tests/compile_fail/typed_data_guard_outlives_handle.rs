@@ -0,0 +1,7 @@
+use dart::dart_handle::{TypedDataGuard, UnverifiedDartHandle};
+
+fn escape(handle: UnverifiedDartHandle) -> Option<TypedDataGuard<'static, u8>> {
+    TypedDataGuard::acquire(&handle).unwrap()
+}
+
+fn main() {}